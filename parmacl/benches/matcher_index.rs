@@ -0,0 +1,48 @@
+//! Compares parsing with and without a compiled matcher index. Run with `cargo bench --bench matcher_index`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use parmacl::{Parser, RegexOrText, OptionHasValue};
+
+const MATCHER_COUNT: usize = 500;
+const ITERATIONS: usize = 2_000;
+
+fn create_parser() -> Parser {
+    let mut parser: Parser = Parser::new();
+    parser.set_multi_char_option_code_requires_double_announcer(true);
+    for idx in 0..MATCHER_COUNT {
+        parser
+            .push_new_param_matcher(&format!("command-{}", idx))
+                .some_param_indices(&[0])
+                .some_value_text(RegexOrText::with_text(&format!("command-{}", idx)));
+        parser
+            .push_new_option_matcher(&format!("option-{}", idx))
+                .some_option_codes(&[RegexOrText::with_text(&format!("option-{}", idx))])
+                .set_option_has_value(OptionHasValue::IfPossible);
+    }
+    parser.push_new_param_matcher("param");
+    parser
+}
+
+fn time_parse(parser: &Parser, line: &str) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(parser.parse_line(black_box(line)).unwrap());
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let last_idx = MATCHER_COUNT - 1;
+    let line = format!("bin COMMAND-{0} --option-{0} value --OPTION-{1} p1 p2", last_idx, last_idx / 2);
+
+    let mut parser = create_parser();
+    let linear = time_parse(&parser, &line);
+    parser.compile();
+    let compiled = time_parse(&parser, &line);
+
+    println!("{} matchers, {} parses", MATCHER_COUNT * 2 + 1, ITERATIONS);
+    println!("linear:   {:?} ({:?} per parse)", linear, linear / ITERATIONS as u32);
+    println!("compiled: {:?} ({:?} per parse)", compiled, compiled / ITERATIONS as u32);
+    println!("speedup:  {:.1}x", linear.as_secs_f64() / compiled.as_secs_f64());
+}
@@ -1,4 +1,23 @@
+use std::borrow::Cow;
 use crate::matcher::Matcher;
+use crate::span::Span;
+use crate::config_file::ConfigLocation;
+use crate::regex_or_text::RegexCaptures;
+
+/// Specifies where the value of an argument came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueSource {
+    /// The argument was parsed from the command line (or environmental arguments).
+    CommandLine,
+    /// The argument was not in the command line. Its value was taken from the matcher's
+    /// [environment variable](crate::Matcher::env_var_name).
+    Environment,
+    /// The argument was not in the command line. Its value was read from a [configuration file](crate::Parser::config_files)
+    /// at the specified location.
+    ConfigFile(ConfigLocation),
+    /// The argument was not in the command line. Its value is the matcher's [default value](crate::Matcher::default_value).
+    Default,
+}
 
 /// Trait with getters for properties common to all [Arg enum](Arg) variant properties.
 pub trait ArgProperties<O: Default, P: Default> {
@@ -12,6 +31,11 @@ pub trait ArgProperties<O: Default, P: Default> {
     /// be the same as [char_index](Self::char_index) and be exact. If environmental arguments were parsed, this will
     /// an approximate position in the line.
     fn env_line_approximate_char_index(&self) -> usize;
+    /// Position of the parsed argument. If a line was parsed, this will be the position in the line. If environmental
+    /// arguments were parsed, this will be the position in the environmental argument where the parsed argument started.
+    fn span(&self) -> Span;
+    /// Text of the parsed argument exactly as entered (ie. within [span](Self::span)), including any quote and escape characters.
+    fn raw_text(&self) -> &str;
     /// Index of the parsed argument.
     fn arg_index(&self) -> usize;
     /// Index of the environment argument in which the parsed argument started.  If a line is parsed, this will always
@@ -32,6 +56,11 @@ pub struct OptionProperties<'a, O: Default, P: Default> {
     /// be the same as [char_index](Self::char_index) and be exact. If environmental arguments were parsed, this will
     /// an approximate position in the line.
     pub env_line_approximate_char_index: usize,
+    /// Position of the parsed argument. If a line was parsed, this will be the position in the line. If environmental
+    /// arguments were parsed, this will be the position in the environmental argument where the parsed argument started.
+    pub span: Span,
+    /// Text of the parsed argument exactly as entered (ie. within [span](Self::span)), including any quote and escape characters.
+    pub raw_text: Cow<'a, str>,
     /// Index of the parsed argument.
     pub arg_index: usize,
     /// Index of the environment argument in which the parsed argument started.  If a line is parsed, this will always
@@ -39,10 +68,84 @@ pub struct OptionProperties<'a, O: Default, P: Default> {
     pub env_arg_index: usize,
     /// Index of the parsed option arguments. (Parameters arguments are ignored in this index.)
     pub option_index: usize,
-    /// Option code of the option argument.
-    pub code: String,
+    /// Option code of the option argument. If the option code was [abbreviated](crate::Parser::option_code_abbreviations), this
+    /// holds the full option code.
+    pub code: Cow<'a, str>,
+    /// Position of the option code including its announcer character(s).
+    pub code_span: Span,
     /// Text in the option value. If option did not have a value, then this holds `None`.
-    pub value_text: Option<String>,
+    pub value_text: Option<Cow<'a, str>>,
+    /// Position of the option value (including quotes). If option did not have a value or the value did not come from the
+    /// command line, then this holds `None`.  If environmental arguments were parsed, the value may be in a different
+    /// environmental argument to the option code.
+    pub value_span: Option<Span>,
+    /// Text of the option value exactly as entered (ie. within [value_span](Self::value_span)), including any quote and escape
+    /// characters. If option did not have a value or the value did not come from the command line, then this holds `None`.
+    pub value_raw_text: Option<Cow<'a, str>>,
+    /// The values of the option. If the matcher has a [value_delimiter](crate::Matcher::value_delimiter), the option value is
+    /// split into delimited values (with quotes removed).  If the matcher's [option_value_arity](crate::Matcher::option_value_arity)
+    /// allows more than one value argument, the additional value arguments are appended.  Otherwise this holds
+    /// [value_text](Self::value_text) if the option has a value or is empty if it does not.  [span](Self::span),
+    /// [value_span](Self::value_span) and [raw_text](Self::raw_text) only cover the first value argument.  Values are borrowed
+    /// from the line if they are unchanged by parsing (eg. do not contain quotes or escapes).
+    pub values: Vec<Cow<'a, str>>,
+    /// If the matcher has a [map_separator](crate::Matcher::map_separator), the key/value pairs split from
+    /// [values](Self::values). With [`MapDuplicates::LastWins`](crate::MapDuplicates::LastWins), pairs whose key occurs again later
+    /// (in this or a subsequent option with the same matcher) are not included.  Otherwise empty.
+    pub pairs: Vec<(String, String)>,
+    /// Where the option came from. If not [CommandLine](ValueSource::CommandLine), the option was not in the command line and
+    /// the positions of the option are the end of the command line.
+    pub value_source: ValueSource,
+    /// False if the option was negated. That is, its code had the matcher's [negation prefix](crate::Matcher::negation_prefix) or
+    /// it was announced with one of the matcher's [negating announcer characters](crate::Matcher::negating_announcer_chars).
+    /// An option which never has a value and is not from the command line, is disabled if its fallback value is `false`,
+    /// `no`, `off` or `0`. See [Parser.config_files](crate::Parser::config_files). Otherwise true.
+    pub enabled: bool,
+    /// The [option announcer character](crate::Parser::option_announcer_chars) which announced the option.  For options not from
+    /// the command line, this is the Parser's first option announcer character.
+    pub announcer_char: char,
+    /// Number of times the option occurred. Always 1 unless the matcher is [counted](crate::Matcher::counted), in which case
+    /// repeated occurrences (eg. `-v -v` or `-vv`) are merged into the first occurrence and this holds the total.
+    pub count: usize,
+    /// Case sensitivity used when the option was matched. Used to capture regex groups.
+    pub(crate) captures_case_sensitive: bool,
+}
+
+impl<'a, O: Default, P: Default> OptionProperties<'a, O, P> {
+    /// If the option code was matched by a regex in the matcher's [option_codes](crate::Matcher::option_codes), the regex's
+    /// capture groups (eg. the level in `-O2` matched by `O(\d)`).  For a negated option, these are from the code without the
+    /// [negation prefix](crate::Matcher::negation_prefix). Otherwise `None`.
+    /// 
+    /// The groups are captured each time this is called.
+    pub fn code_captures(&self) -> Option<RegexCaptures> {
+        if self.value_source != ValueSource::CommandLine {
+            None
+        } else {
+            let matcher_codes = self.matcher.option_codes_as_slice();
+            let case_sensitive = self.captures_case_sensitive;
+            let code_matched = matcher_codes.iter().any(|matcher_code| matcher_code.is_match(&self.code, case_sensitive));
+            let code = if code_matched {
+                Some(self.code.as_ref())
+            } else {
+                self.matcher.strip_negation_prefix(&self.code, case_sensitive)
+            };
+            code.and_then(|code| matcher_codes.iter()
+                .find(|matcher_code| matcher_code.is_match(code, case_sensitive))
+                .and_then(|matcher_code| matcher_code.captures(code, case_sensitive))
+            )
+        }
+    }
+
+    /// If the matcher's [value_text](crate::Matcher::value_text) is a regex which matches the option's
+    /// [value_text](Self::value_text), the regex's capture groups. Otherwise `None`.  Only the first value argument is captured.
+    /// 
+    /// The groups are captured each time this is called.
+    pub fn value_captures(&self) -> Option<RegexCaptures> {
+        match (self.matcher.value_text(), self.value_text.as_ref()) {
+            (Some(matcher_value_text), Some(value_text)) => matcher_value_text.captures(value_text, self.captures_case_sensitive),
+            _ => None,
+        }
+    }
 }
 
 impl<'a, O: Default, P: Default> ArgProperties<O, P> for OptionProperties<'a, O, P> {
@@ -55,6 +158,12 @@ impl<'a, O: Default, P: Default> ArgProperties<O, P> for OptionProperties<'a, O,
     fn env_line_approximate_char_index(&self) -> usize {
         self.env_line_approximate_char_index
     }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn raw_text(&self) -> &str {
+        &self.raw_text
+    }
     fn arg_index(&self) -> usize {
         self.arg_index
     }
@@ -76,6 +185,11 @@ pub struct ParamProperties<'a, O: Default, P: Default> {
     /// be the same as [char_index](Self::char_index) and be exact. If environmental arguments were parsed, this will
     /// an approximate position in the line.
     pub env_line_approximate_char_index: usize,
+    /// Position of the parsed argument. If a line was parsed, this will be the position in the line. If environmental
+    /// arguments were parsed, this will be the position in the environmental argument where the parsed argument started.
+    pub span: Span,
+    /// Text of the parsed argument exactly as entered (ie. within [span](Self::span)), including any quote and escape characters.
+    pub raw_text: Cow<'a, str>,
     /// Index of the parsed argument.
     pub arg_index: usize,
     /// Index of the environment argument in which the parsed argument started.  If a line is parsed, this will always
@@ -84,7 +198,23 @@ pub struct ParamProperties<'a, O: Default, P: Default> {
     /// Index of the parsed parameter arguments. (Option arguments are ignored in this index.)
     pub param_index: usize,
     /// Text in the parameter.
-    pub value_text: String,
+    pub value_text: Cow<'a, str>,
+    /// Where the parameter came from. If not [CommandLine](ValueSource::CommandLine), the parameter was not in the command line and
+    /// the positions of the parameter are the end of the command line.
+    pub value_source: ValueSource,
+    /// Case sensitivity used when the parameter was matched. Used to capture regex groups.
+    pub(crate) captures_case_sensitive: bool,
+}
+
+impl<'a, O: Default, P: Default> ParamProperties<'a, O, P> {
+    /// If the matcher's [value_text](crate::Matcher::value_text) is a regex which matches the parameter, the regex's capture
+    /// groups (eg. `host` and `port` from `(?P<host>[^:]+):(?P<port>\d+)`). Otherwise `None`.
+    /// 
+    /// The groups are captured each time this is called.
+    pub fn value_captures(&self) -> Option<RegexCaptures> {
+        self.matcher.value_text().as_ref()
+            .and_then(|matcher_value_text| matcher_value_text.captures(&self.value_text, self.captures_case_sensitive))
+    }
 }
 
 impl<'a, O: Default, P: Default> ArgProperties<O, P> for ParamProperties<'a, O, P> {
@@ -97,6 +227,12 @@ impl<'a, O: Default, P: Default> ArgProperties<O, P> for ParamProperties<'a, O,
     fn env_line_approximate_char_index(&self) -> usize {
         self.env_line_approximate_char_index
     }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn raw_text(&self) -> &str {
+        &self.raw_text
+    }
     fn arg_index(&self) -> usize {
         self.arg_index
     }
@@ -118,12 +254,17 @@ pub struct BinaryProperties<'a, O: Default, P: Default> {
     /// be the same as [char_index](Self::char_index) and be exact. If environmental arguments were parsed, this will
     /// an approximate position in the line. Normally 0.
     pub env_line_approximate_char_index: usize,
+    /// Position of the parsed argument. If a line was parsed, this will be the position in the line. If environmental
+    /// arguments were parsed, this will be the position in the environmental argument.
+    pub span: Span,
+    /// Text of the parsed argument exactly as entered (ie. within [span](Self::span)), including any quote and escape characters.
+    pub raw_text: Cow<'a, str>,
     /// Index of the parsed argument. Always 0.
     pub arg_index: usize,
     /// Index of the environment argument in which the parsed argument started.  Normally 0.
     pub env_arg_index: usize,
     /// Text normally specifying the binary name or path.
-    pub value_text: String,
+    pub value_text: Cow<'a, str>,
 }
 
 impl<'a, O: Default, P: Default> ArgProperties<O, P> for BinaryProperties<'a, O, P> {
@@ -136,6 +277,12 @@ impl<'a, O: Default, P: Default> ArgProperties<O, P> for BinaryProperties<'a, O,
     fn env_line_approximate_char_index(&self) -> usize {
         self.env_line_approximate_char_index
     }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn raw_text(&self) -> &str {
+        &self.raw_text
+    }
     fn arg_index(&self) -> usize {
         self.arg_index
     }
@@ -0,0 +1,111 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use crate::arg::{Arg, Args};
+use crate::env_char::EnvChar;
+use crate::matcher::{Matcher, DefaultTagType};
+use crate::parse_error::{ParseError};
+use crate::parse_state::{ParseState};
+use crate::parser::{Parser};
+use crate::termination::{Termination};
+
+/// An iterator which parses a command line on demand, returning each [argument](Arg) as it is parsed.
+///
+/// Created with [Parser.iter_line](crate::Parser::iter_line). The line is only parsed as far as needed to return the next
+/// argument, so a caller can stop iterating (eg. after finding a subcommand) without the rest of the line being parsed and
+/// without an [Args](crate::Args) array being built for the whole line.
+///
+/// Each item is a result. If a parse error is encountered, an error result is returned and iteration finishes.  Arguments
+/// are returned in the same order as [ArgVisitor](crate::ArgVisitor) receives them (with the same differences from
+/// [parse_line](crate::Parser::parse_line) for counted and map options).
+pub struct ArgIter<'a, O: Default = DefaultTagType, P: Default = DefaultTagType> {
+    parser: &'a Parser<O, P>,
+    parse_state: ParseState<'a>,
+    args: Args<'a, O, P>,
+    completed_args: VecDeque<Arg<'a, O, P>>,
+    matched_matchers: Vec<&'a Matcher<O, P>>,
+    termination: Option<Termination>,
+    error: Option<ParseError>,
+    finished: bool,
+}
+
+impl<'a, O: Default, P: Default> ArgIter<'a, O, P> {
+    /// Create a new ArgIter which parses `line` with `parser`.
+    pub fn new(parser: &'a Parser<O, P>, line: &'a str) -> Self {
+        ArgIter {
+            parser,
+            parse_state: ParseState::new(
+                Cow::Borrowed(line),
+                parser.first_arg_is_binary(),
+                parser.multi_char_option_code_requires_double_announcer(),
+            ),
+            args: Vec::new(),
+            completed_args: VecDeque::new(),
+            matched_matchers: Vec::new(),
+            termination: None,
+            error: None,
+            finished: false,
+        }
+    }
+
+    /// If parsing was terminated by a [parse terminate character](crate::Parser::parse_terminate_chars), the termination
+    /// details.  Only available once the iterator has returned all arguments.
+    pub fn termination(&self) -> Option<&Termination> {
+        self.termination.as_ref()
+    }
+
+    /// Parse until at least one argument is completed or the line is finished.
+    fn parse_next(&mut self) -> Result<(), ParseError> {
+        while self.completed_args.is_empty() && !self.finished {
+            match self.parse_state.current_char() {
+                Some(char) => {
+                    let more = self.parser.process_char(&mut self.parse_state, EnvChar::Unicode(char), &mut self.args)?;
+                    self.take_completed_args()?;
+
+                    if more {
+                        self.parse_state.increment_env_line_approximate_char_idx();
+                        self.parse_state.increment_env_arg_char_idx(char);
+                    } else {
+                        // ignore rest of line
+                        self.termination = Some(self.parser.create_termination(&self.parse_state, char));
+                        self.finish()?;
+                    }
+                }
+                None => self.finish()?,
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), ParseError> {
+        self.finished = true;
+        self.parser.finalise_parse(&mut self.parse_state, &mut self.args)?;
+        self.parser.finalise_pending_option_values(&mut self.parse_state, &self.args)?;
+        self.take_completed_args()?;
+        self.parser.add_unmatched_fallback_args(&mut self.parse_state, &self.matched_matchers, &mut self.args)?;
+        self.take_completed_args()
+    }
+
+    fn take_completed_args(&mut self) -> Result<(), ParseError> {
+        let completed_count = self.parser.prepare_completed_args(&mut self.parse_state, &mut self.args, &mut self.matched_matchers)?;
+        self.completed_args.extend(self.args.drain(..completed_count));
+        Ok(())
+    }
+}
+
+impl<'a, O: Default, P: Default> Iterator for ArgIter<'a, O, P> {
+    type Item = Result<Arg<'a, O, P>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.completed_args.is_empty() && self.error.is_none() {
+            if let Err(error) = self.parse_next() {
+                self.finished = true;
+                self.error = Some(error);
+            }
+        }
+        match self.completed_args.pop_front() {
+            Some(arg) => Some(Ok(arg)),
+            // arguments completed before an error are returned first
+            None => self.error.take().map(Err),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use std::ops::ControlFlow;
+use crate::arg::{BinaryProperties, OptionProperties, ParamProperties};
+use crate::matcher::{DefaultTagType};
+use crate::parse_error::{ParseError};
+
+/// Receives parsed arguments from [Parser.parse_line_with](crate::Parser::parse_line_with) as the parser completes them,
+/// instead of the arguments being collected into an [Args](crate::Args) array.
+///
+/// All functions have default implementations so only the required functions need to be implemented.  The `on_` argument
+/// functions return [ControlFlow::Break] to stop parsing the rest of the line.
+///
+/// Arguments are passed in the order they occur in the line, followed by any [fallback](crate::ValueSource) arguments.
+/// An option which takes several value arguments (see [option_value_arity](crate::Matcher::option_value_arity)) is passed
+/// once all its values have been parsed. As arguments are not collected:
+/// * Repeated occurrences of [counted](crate::Matcher::counted) options are passed separately and not merged.
+/// * For [map options](crate::Matcher::map_separator), duplicate keys are only detected within each option argument.
+pub trait ArgVisitor<'a, O: Default = DefaultTagType, P: Default = DefaultTagType> {
+    /// Called with the binary argument.
+    fn on_binary(&mut self, _properties: BinaryProperties<'a, O, P>) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called with each option argument.
+    fn on_option(&mut self, _properties: OptionProperties<'a, O, P>) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called with each parameter argument.
+    fn on_param(&mut self, _properties: ParamProperties<'a, O, P>) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called with the error if parsing fails. The error is also returned by [parse_line_with](crate::Parser::parse_line_with).
+    fn on_error(&mut self, _error: &ParseError) {
+    }
+
+    /// Called when the word following an option code (separated by whitespace) could either be the option's value or a
+    /// parameter. That is, the option's matcher has [OptionHasValue::IfPossible](crate::OptionHasValue::IfPossible).
+    /// `option_code` is the option's code and `value_text` is the word.
+    ///
+    /// Return true to try the word as the option's value (falling back to a parameter if no matcher matches the option with
+    /// the value). This is the default and is the same as [parse_line](crate::Parser::parse_line). Return false for the
+    /// word to be a parameter.
+    fn is_option_value(&mut self, _option_code: &str, _value_text: &str) -> bool {
+        true
+    }
+}
@@ -0,0 +1,121 @@
+use crate::arg::{Args};
+use crate::parser::{Parser};
+use crate::span::{Span};
+use crate::termination::{Termination};
+
+/// A command parsed from a multi-command line by [Parser.parse_commands](crate::Parser::parse_commands).
+#[derive(Debug)]
+pub struct Command<'a, O: Default, P: Default> {
+    /// The parsed arguments of the command.
+    pub args: Args<'a, O, P>,
+    /// Position of the command's text in the line. Excludes the separators on either side.
+    pub span: Span,
+    /// The [command separator](crate::Parser::command_separators) which connected this command to the next command.  For the
+    /// last command in the line, this holds `None`.
+    pub separator: Option<String>,
+    /// Position of [separator](Self::separator) in the line.
+    pub separator_span: Option<Span>,
+    /// If parsing of the command was terminated by a [parse terminate character](crate::Parser::parse_terminate_chars), this holds
+    /// the termination details.  This can only occur in the last command in the line.
+    pub termination: Option<Termination>,
+}
+
+pub(crate) struct CommandSegment {
+    pub(crate) span: Span,
+    pub(crate) separator: Option<String>,
+    pub(crate) separator_span: Option<Span>,
+}
+
+/// Splits a line into command segments at command separators which are not quoted or escaped. Splitting stops at a parse
+/// terminate character so that the remainder of the line is left for the last command's termination.
+pub(crate) fn split_commands<O: Default, P: Default>(parser: &Parser<O, P>, line: &str) -> Vec<CommandSegment> {
+    let mut segments = Vec::new();
+    let mut separators: Vec<&str> = parser.command_separators().iter().map(|separator| separator.as_str())
+        .filter(|separator| !separator.is_empty()).collect();
+    // longest separators first so that "||" is preferred over "|"
+    separators.sort_by_key(|separator| std::cmp::Reverse(separator.len()));
+
+    let mut start_char_idx = 0;
+    let mut start_byte_idx = 0;
+    let mut char_idx = 0;
+    let mut quote_char: Option<char> = None;
+    let mut at_value_start = true;
+    let mut escaped = false;
+    let mut char_indices = line.char_indices().peekable();
+
+    while let Some((byte_idx, unicode_char)) = char_indices.next() {
+        if escaped {
+            escaped = false;
+            at_value_start = false;
+        } else {
+            if Some(unicode_char) == *parser.escape_char() {
+                escaped = true;
+            } else {
+                if let Some(unwrapped_quote_char) = quote_char {
+                    if unicode_char == unwrapped_quote_char {
+                        let quote_is_embedded = parser.embed_quote_char_with_double() &&
+                            matches!(char_indices.peek(), Some((_, next_char)) if *next_char == unwrapped_quote_char);
+                        if quote_is_embedded {
+                            char_indices.next();
+                            char_idx += 1;
+                        } else {
+                            quote_char = None;
+                        }
+                    }
+                    at_value_start = false;
+                } else {
+                    if at_value_start && parser.quote_chars().contains(&unicode_char) {
+                        quote_char = Some(unicode_char);
+                        at_value_start = false;
+                    } else {
+                        if parser.parse_terminate_chars().contains(&unicode_char) {
+                            break;
+                        } else {
+                            let optioned_separator = separators.iter().find(|separator| line[byte_idx..].starts_with(**separator));
+                            if let Some(separator) = optioned_separator {
+                                let separator_char_count = separator.chars().count();
+                                let separator_end_byte_idx = byte_idx + separator.len();
+                                segments.push(CommandSegment {
+                                    span: Span::new(start_char_idx, char_idx, start_byte_idx, byte_idx),
+                                    separator: Some(String::from(*separator)),
+                                    separator_span: Some(Span::new(char_idx, char_idx + separator_char_count, byte_idx, separator_end_byte_idx)),
+                                });
+
+                                for _ in 1..separator_char_count {
+                                    char_indices.next();
+                                }
+                                char_idx += separator_char_count;
+                                start_char_idx = char_idx;
+                                start_byte_idx = separator_end_byte_idx;
+                                at_value_start = true;
+                                continue;
+                            } else {
+                                at_value_start = unicode_char.is_whitespace() || parser.option_value_announcer_chars().contains(&unicode_char);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        char_idx += 1;
+    }
+
+    let last_segment_is_empty = line[start_byte_idx..].trim().is_empty();
+    if segments.is_empty() || !last_segment_is_empty {
+        let end_char_idx = start_char_idx + line[start_byte_idx..].chars().count();
+        segments.push(CommandSegment {
+            span: Span::new(start_char_idx, end_char_idx, start_byte_idx, line.len()),
+            separator: None,
+            separator_span: None,
+        });
+    } else {
+        // ignore a trailing separator so that the last command does not have a separator
+        if let Some(last_segment) = segments.last_mut() {
+            last_segment.separator = None;
+            last_segment.separator_span = None;
+        }
+    }
+
+    segments
+}
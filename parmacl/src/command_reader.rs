@@ -0,0 +1,179 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::{fmt::Display, error::Error, io, io::BufRead, mem, str::Utf8Error};
+use crate::arg::{Args};
+use crate::command::{Command, CommandSegment, split_commands};
+use crate::matcher::{DefaultTagType};
+use crate::parse_error::{ParseError};
+use crate::parse_state::{ParseBuffers};
+use crate::parser::{Parser};
+
+/// A command read from an input by a [CommandReader].
+#[derive(Debug)]
+pub struct ReadCommand<'a, O: Default, P: Default> {
+    /// The line number (starting from 1) of the line in the input which holds the command.
+    pub line_number: usize,
+    /// The parsed command. Spans are positions in the line holding the command.
+    pub command: Command<'a, O, P>,
+}
+
+/// Error returned by a [CommandReader] when a line cannot be read or a command cannot be parsed.
+// ParseError is large but errors are rare
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub enum ReadCommandError {
+    /// A line could not be read from the input. Holds the IO error.  Iteration finishes after this error.
+    Io(io::Error),
+    /// A line is not valid UTF-8.  The line is skipped and iteration continues with the next line.
+    InvalidUtf8 {
+        /// The line number (starting from 1) of the invalid line.
+        line_number: usize,
+        /// Where the line is invalid.
+        error: Utf8Error,
+    },
+    /// A command could not be parsed. The [ParseError] holds the line and column numbers where the error was detected.
+    Parse(ParseError),
+}
+
+impl Error for ReadCommandError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadCommandError::Io(error) => Some(error),
+            ReadCommandError::InvalidUtf8 { error, .. } => Some(error),
+            ReadCommandError::Parse(error) => Some(error),
+        }
+    }
+}
+
+impl Display for ReadCommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadCommandError::Io(error) => write!(f, "{}", error),
+            ReadCommandError::InvalidUtf8 { line_number, error } => write!(f, "Line {}: {}", line_number, error),
+            ReadCommandError::Parse(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// An iterator which reads and parses commands from an input one line at a time.
+///
+/// Created with [Parser.read_commands](crate::Parser::read_commands). Each line of the input is split into commands at the
+/// parser's [command separators](crate::Parser::command_separators) and each command is parsed as if it were a separate line
+/// (see [Parser.parse_commands](crate::Parser::parse_commands)).  A line is only read from the input after all the commands
+/// in the previous line have been returned, so large inputs do not need to be held in memory.  Blank lines are skipped.
+///
+/// Each line is parsed separately, so a quoted argument cannot continue onto the next line.  A command in which a quote is
+/// not closed by the end of its line returns a missing closing quote [ParseError] (eg.
+/// [ParamMissingClosingQuoteCharacter](crate::ParseErrorTypeId::ParamMissingClosingQuoteCharacter)).
+///
+/// Each item is a result. If a command cannot be parsed, an error result is returned and iteration continues with the next
+/// command. If a line is not valid UTF-8, an error result is returned and iteration continues with the next line. If the
+/// input cannot be read, an error result is returned and iteration finishes.
+pub struct CommandReader<'p, R: BufRead, O: Default = DefaultTagType, P: Default = DefaultTagType> {
+    parser: &'p Parser<O, P>,
+    reader: R,
+    /// Owned so that the line can be parsed without borrowing from the reader. It is moved into each parse and taken back
+    /// afterwards.
+    line: Cow<'p, str>,
+    line_number: usize,
+    segments: VecDeque<CommandSegment>,
+    buffers: ParseBuffers,
+    finished: bool,
+}
+
+impl<'p, R: BufRead, O: Default, P: Default> CommandReader<'p, R, O, P> {
+    /// Create a new CommandReader which parses the lines read from `reader` with `parser`.
+    pub fn new(parser: &'p Parser<O, P>, reader: R) -> Self {
+        CommandReader {
+            parser,
+            reader,
+            line: Cow::Owned(String::from("")),
+            line_number: 0,
+            segments: VecDeque::new(),
+            buffers: ParseBuffers::new(),
+            finished: false,
+        }
+    }
+
+    /// The line number (starting from 1) of the last line read. Zero if no lines have been read.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Read the next line and split it into command segments. Returns false at the end of the input.
+    fn read_line(&mut self) -> Result<bool, ReadCommandError> {
+        // reuse the line's allocation
+        let mut line_bytes = mem::take(&mut self.line).into_owned().into_bytes();
+        line_bytes.clear();
+        if self.reader.read_until(b'\n', &mut line_bytes).map_err(ReadCommandError::Io)? == 0 {
+            Ok(false)
+        } else {
+            self.line_number += 1;
+            let mut line = match String::from_utf8(line_bytes) {
+                Ok(line) => line,
+                Err(error) => {
+                    return Err(ReadCommandError::InvalidUtf8 { line_number: self.line_number, error: error.utf8_error() });
+                }
+            };
+            let trimmed_len = line.trim_end_matches(['\n', '\r']).len();
+            line.truncate(trimmed_len);
+            if !line.trim().is_empty() {
+                self.segments.extend(split_commands(self.parser, &line));
+            }
+            self.line = Cow::Owned(line);
+            Ok(true)
+        }
+    }
+
+    fn parse_segment(&mut self, segment: CommandSegment) -> Result<ReadCommand<'p, O, P>, ReadCommandError> {
+        let mut args: Args<'p, O, P> = Vec::new();
+        // argument text cannot borrow from the line as it is overwritten by the next line
+        let result = self.parser.parse_line_range_into(&mut self.line, segment.span.char_index, segment.span.byte_index,
+            segment.span.end_byte_index, &mut self.buffers, &mut args);
+
+        match result {
+            Ok(termination) => Ok(ReadCommand {
+                line_number: self.line_number,
+                command: Command {
+                    args,
+                    span: segment.span,
+                    separator: segment.separator,
+                    separator_span: segment.separator_span,
+                    termination,
+                },
+            }),
+            Err(mut error) => {
+                error.context.line_number = Some(self.line_number);
+                error.context.column_number = Some(error.line_char_index + 1);
+                Err(ReadCommandError::Parse(error))
+            }
+        }
+    }
+}
+
+impl<'p, R: BufRead, O: Default, P: Default> Iterator for CommandReader<'p, R, O, P> {
+    type Item = Result<ReadCommand<'p, O, P>, ReadCommandError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(segment) = self.segments.pop_front() {
+                return Some(self.parse_segment(segment));
+            }
+
+            if self.finished {
+                return None;
+            }
+
+            match self.read_line() {
+                Ok(true) => {}
+                Ok(false) => self.finished = true,
+                Err(error) => {
+                    if matches!(error, ReadCommandError::Io(_)) {
+                        self.finished = true;
+                    }
+                    return Some(Err(error));
+                }
+            }
+        }
+    }
+}
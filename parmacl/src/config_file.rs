@@ -0,0 +1,205 @@
+use std::{fmt::Display, error::Error, fs, io, path::Path};
+
+/// The location of a value in a configuration file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigLocation {
+    /// The path (or other name) of the configuration file.
+    pub path: String,
+    /// The line number (starting from 1) of the value in the configuration file.
+    pub line_number: usize,
+}
+
+/// A key/value pair read from a [ConfigFile].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigEntry {
+    /// The key.  If the entry is within a section, the key is prefixed with the section name and a period (eg. `section.key`).
+    pub key: String,
+    /// The value with quotes and escape characters removed.
+    pub value: String,
+    /// The line number (starting from 1) of the entry in the configuration file.
+    pub line_number: usize,
+}
+
+/// Key/value pairs read from a simple INI or TOML style configuration file.  The values can be used as fallbacks for arguments
+/// which are not in a command line. See [Parser.config_files](crate::Parser::config_files).
+///
+/// The following syntax is supported:
+/// * `key = value` or `key: value` entries, one per line.  Keys are mapped to [matcher names](crate::Matcher::name).  Parsing
+///   a line returns an [UnknownConfigKey](crate::ParseErrorTypeId::UnknownConfigKey) error if a key is not the name of a
+///   matcher.
+/// * `[section]` headers. Keys following a header are prefixed with the section name and a period.
+/// * Lines starting with `#` or `;` are comments. Blank lines are ignored.
+/// * Values can be enclosed in double quotes (which support the escape sequences `\"`, `\\`, `\n` and `\t`) or single quotes
+///   (literal). Unquoted values are trimmed and end at a ` #` comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigFile {
+    path: String,
+    entries: Vec<ConfigEntry>,
+}
+
+impl ConfigFile {
+    /// Read and parse a configuration file.
+    pub fn read<T: AsRef<Path>>(path: T) -> Result<Self, ConfigFileError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|error| ConfigFileError::Io(path.display().to_string(), error))?;
+        Self::parse(&path.display().to_string(), &text)
+    }
+
+    /// Parse the text of a configuration file. `path` is used to identify the file in [ConfigLocation]s and errors.
+    pub fn parse(path: &str, text: &str) -> Result<Self, ConfigFileError> {
+        let mut entries = Vec::new();
+        let mut section = String::from("");
+
+        for (line_idx, line) in text.lines().enumerate() {
+            let line_number = line_idx + 1;
+            let trimmed_line = line.trim();
+            if trimmed_line.is_empty() || trimmed_line.starts_with('#') || trimmed_line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(header) = trimmed_line.strip_prefix('[') {
+                match header.strip_suffix(']') {
+                    Some(section_name) => section = String::from(section_name.trim()),
+                    None => return Err(ConfigFileError::new_syntax(path, line_number, "Section header missing closing bracket")),
+                }
+            } else {
+                let separator_idx = trimmed_line.find(['=', ':'])
+                    .ok_or_else(|| ConfigFileError::new_syntax(path, line_number, "Expected key and value separated by '=' or ':'"))?;
+                let key = trimmed_line[..separator_idx].trim();
+                if key.is_empty() {
+                    return Err(ConfigFileError::new_syntax(path, line_number, "Key is empty"));
+                }
+                let value = parse_value(trimmed_line[separator_idx + 1..].trim())
+                    .map_err(|message| ConfigFileError::new_syntax(path, line_number, message))?;
+
+                let key = if section.is_empty() {
+                    String::from(key)
+                } else {
+                    format!("{}.{}", section, key)
+                };
+                entries.push(ConfigEntry { key, value, line_number });
+            }
+        }
+
+        Ok(ConfigFile {
+            path: String::from(path),
+            entries,
+        })
+    }
+
+    /// The path (or other name) of the configuration file.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The entries read from the configuration file in the order they appear in the file.
+    pub fn entries(&self) -> &[ConfigEntry] {
+        &self.entries
+    }
+
+    /// Find the last entry with the specified key.
+    pub fn find_entry(&self, key: &str) -> Option<&ConfigEntry> {
+        self.entries.iter().rev().find(|entry| entry.key == key)
+    }
+
+    /// Get the location of an entry in this configuration file.
+    pub fn location(&self, entry: &ConfigEntry) -> ConfigLocation {
+        ConfigLocation {
+            path: self.path.clone(),
+            line_number: entry.line_number,
+        }
+    }
+}
+
+fn parse_value(raw_value: &str) -> Result<String, &'static str> {
+    if let Some(quoted) = raw_value.strip_prefix('"') {
+        let mut value = String::with_capacity(quoted.len());
+        let mut chars = quoted.chars();
+        loop {
+            match chars.next() {
+                None => return Err("Value missing closing quote"),
+                Some('"') => break,
+                Some('\\') => {
+                    match chars.next() {
+                        Some('n') => value.push('\n'),
+                        Some('t') => value.push('\t'),
+                        Some(escaped_char @ ('"' | '\\')) => value.push(escaped_char),
+                        _ => return Err("Invalid escape sequence in value"),
+                    }
+                }
+                Some(value_char) => value.push(value_char),
+            }
+        }
+        check_after_quoted_value(chars.as_str())?;
+        Ok(value)
+    } else {
+        if let Some(quoted) = raw_value.strip_prefix('\'') {
+            match quoted.split_once('\'') {
+                None => Err("Value missing closing quote"),
+                Some((value, rest)) => {
+                    check_after_quoted_value(rest)?;
+                    Ok(String::from(value))
+                }
+            }
+        } else {
+            let value = match raw_value.find(" #") {
+                Some(comment_idx) => &raw_value[..comment_idx],
+                None => raw_value,
+            };
+            Ok(String::from(value.trim()))
+        }
+    }
+}
+
+fn check_after_quoted_value(rest: &str) -> Result<(), &'static str> {
+    let rest = rest.trim_start();
+    if rest.is_empty() || rest.starts_with('#') || rest.starts_with(';') {
+        Ok(())
+    } else {
+        Err("Unexpected text after quoted value")
+    }
+}
+
+/// Error returned when a [ConfigFile] cannot be read or parsed.
+#[derive(Debug)]
+pub enum ConfigFileError {
+    /// The file could not be read. Holds the path of the file and the IO error.
+    Io(String, io::Error),
+    /// The file contains a syntax error.
+    Syntax {
+        /// The path (or other name) of the configuration file.
+        path: String,
+        /// The line number (starting from 1) of the syntax error.
+        line_number: usize,
+        /// Description of the error.
+        message: String,
+    },
+}
+
+impl ConfigFileError {
+    fn new_syntax(path: &str, line_number: usize, message: &str) -> Self {
+        ConfigFileError::Syntax {
+            path: String::from(path),
+            line_number,
+            message: String::from(message),
+        }
+    }
+}
+
+impl Error for ConfigFileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigFileError::Io(_, error) => Some(error),
+            ConfigFileError::Syntax { .. } => None,
+        }
+    }
+}
+
+impl Display for ConfigFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigFileError::Io(path, error) => write!(f, "{}: {}", path, error),
+            ConfigFileError::Syntax { path, line_number, message } => write!(f, "{}:{}: {}", path, line_number, message),
+        }
+    }
+}
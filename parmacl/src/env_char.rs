@@ -7,7 +7,13 @@ impl EnvChar {
     pub fn try_get_unicode_non_whitespace(&self) -> Option<char> {
         match self {
             EnvChar::Separator => None,
-            EnvChar::Unicode(char) => Some(*char),
+            EnvChar::Unicode(char) => {
+                if char.is_whitespace() {
+                    None
+                } else {
+                    Some(*char)
+                }
+            },
         }
     }
 }
\ No newline at end of file
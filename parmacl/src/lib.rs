@@ -19,7 +19,7 @@
 //! 1. If necessary, set relevant properties of the Parser instance to reflect the style of the command line.
 //! 1. Add a [matcher](Matcher) for all possible arguments to the parser. Tag each matcher with the appropriate enum.
 //! 1. Call [Parser.parse_line(command_line)](Parser::parse_line) which will parse the command line and return a result containing
-//! either a [vector of parsed arguments](Args) or an error.
+//!    either a [vector of parsed arguments](Args) or an error.
 //! 1. Loop through returned arguments and process each. The arguments are ordered by appearance in the command line.
 //! 
 //! ## Example
@@ -416,15 +416,15 @@
 //! 
 //! Parmacl considers a command line to have 3 types of arguments
 //! * **Binary name**\
-//! This normally is the first argument in the command line and is normally the path to the application's executable file (binary).
+//!   This normally is the first argument in the command line and is normally the path to the application's executable file (binary).
 //! * **Parameters**\
-//! Strings which the application will interpret. Parameters are typically identified by their order in the command line. In the above
-//! [example](#example), `"1st ""Param"""` and `"param2"` are parameters.
+//!   Strings which the application will interpret. Parameters are typically identified by their order in the command line. In the above
+//!   [example](#example), `"1st ""Param"""` and `"param2"` are parameters.
 //! * **Options**\
-//! An option is an argument identified by a code.  As such it can be placed anywhere in the command line. It can optionally have a value.
-//! If it does not have a value, it behaves like a flag/boolean.  In the above [example](#example), `-a` is an option with code `a` that
-//! behaves like a flag. The options `-B optValue` (code `B`) and `-c "C OptValue"` (code `c`) are options with respective values
-//! `optValue` and `C OptValue`.
+//!   An option is an argument identified by a code.  As such it can be placed anywhere in the command line. It can optionally have a value.
+//!   If it does not have a value, it behaves like a flag/boolean.  In the above [example](#example), `-a` is an option with code `a` that
+//!   behaves like a flag. The options `-B optValue` (code `B`) and `-c "C OptValue"` (code `c`) are options with respective values
+//!   `optValue` and `C OptValue`.
 //! 
 //! Note that these arguments do not necessarily correspond to environment arguments created by a shell and passed to an application.
 //! For example, an option with a value is identified by Parmacl as one argument whereas the shell may identify it as 2 arguments
@@ -460,25 +460,33 @@
 //! # Main types
 //! 
 //! * [Parser](Parser)\
-//! The main object. To parse a command line, create an instance of this, set its properties to reflect the style of the command line,
-//! assign matchers and then call one its parse functions. The result will either be the array of arguments or an error object.
+//!   The main object. To parse a command line, create an instance of this, set its properties to reflect the style of the command line,
+//!   assign matchers and then call one its parse functions. The result will either be the array of arguments or an error object.
 //! * [Matcher](Matcher)\
-//! Each argument must be matched against a matcher.  Typically one matcher is created for each argument however matchers can also
-//! be used to match multiple arguments.
+//!   Each argument must be matched against a matcher.  Typically one matcher is created for each argument however matchers can also
+//!   be used to match multiple arguments.
 //! * [Arg](Arg)\
-//! An enum with 3 variants: Binary, Param, Option. The Parser's parse functions return an array of these variants - each of which
-//! identify an argument the parser found in the command line.
+//!   An enum with 3 variants: Binary, Param, Option. The Parser's parse functions return an array of these variants - each of which
+//!   identify an argument the parser found in the command line.
 //! * [ArgProperties](ArgProperties)\
-//! A trait shared by structs [BinaryProperties](BinaryProperties), [ParamProperties](ParamProperties) and
-//! [OptionProperties](OptionProperties). Instances of these structs are associated with the respective [Arg](Arg) variants returned
-//! by the parse function and provide details about each identified argument.
+//!   A trait shared by structs [BinaryProperties](BinaryProperties), [ParamProperties](ParamProperties) and
+//!   [OptionProperties](OptionProperties). Instances of these structs are associated with the respective [Arg](Arg) variants returned
+//!   by the parse function and provide details about each identified argument.
 //! * [RegexOrText](RegexOrText)\
-//! A struct representing either a Regex or text (string). An instance of RegexOrText can be assigned to the
-//! [option_codes](Matcher::option_codes) or [value_text](Matcher::value_text) Matcher filter properties and determines whether the
-//! filtering is by text or Regex.
+//!   A struct representing either a Regex, a glob pattern or text (string). An instance of RegexOrText can be assigned to the
+//!   [option_codes](Matcher::option_codes) or [value_text](Matcher::value_text) Matcher filter properties and determines whether the
+//!   filtering is by text, Regex or glob pattern.
 //! * [ParseError](ParseError)\
-//! The struct returned with an Error result from a parse function. Specifies the type of error and where in the line the error
-//! occurred.
+//!   The struct returned with an Error result from a parse function. Specifies the type of error and where in the line the error
+//!   occurred. Further details are held in its [ParseErrorContext](ParseErrorContext).
+//! * [Tokenizer](Tokenizer)\
+//!   An iterator returned by [Parser.tokenize](Parser::tokenize) which splits a command line into [tokens](Token) (with their
+//!   [spans](Span)) without matching arguments. Useful for syntax highlighting.
+//! * [LineFormatter](LineFormatter)\
+//!   Renders parsed arguments as a canonical command line. Useful for logging what a user entered in a normalised form.
+//! * [ConfigFile](ConfigFile)\
+//!   Key/value pairs read from an INI or TOML style file. Assigned to a Parser's [config_files](Parser::config_files) to supply
+//!   values for arguments missing from the command line.
 //! 
 //! # Features
 //! 
@@ -498,14 +506,38 @@
 //!     * Parameter or Option
 //!     * Argument indices
 //!     * Parameter indices
-//!     * Parameter text (string or Regex)
+//!     * Parameter text (string, Regex or glob pattern)
 //!     * Option indices
-//!     * Option codes (string or Regex)
+//!     * Option codes (string, Regex or glob pattern)
 //!     * Whether option has value (None, IfPossible, Always)
-//!     * Option value text (string or Regex)
+//!     * Option value text (string, Regex or glob pattern)
 //!     * Whether option value can start with an option announcer character
 //! * Tag parameters and options arguments with with any enum (or any other type) from matcher for easy identification
 //! * Parse error result has properties detailing the type of error and where it occurred.
+//! * Arguments and parse errors have spans (start and end, as character and byte indices) locating them in the command line
+//! * Arguments and parse errors hold their raw text exactly as entered (including quote and escape characters)
+//! * Text following a parse terminate character is returned to the caller (eg. for comments or chaining commands)
+//! * Parse lines containing multiple commands separated by `;`, `&&`, `|` etc.
+//! * Negatable flags (eg. `--no-color` or `+x`/`-x`)
+//! * Counted flags (eg. `-vvv` or `-v -v`)
+//! * Options with lists of values, either delimited (eg. `--include a,b,c`) or as several arguments (eg. `--files x y z`)
+//! * Map options holding key/value pairs (eg. `-D name=value`) with key validation and a duplicate keys policy
+//! * Option values restricted to a list of choices, with canonical spelling, help text and completion
+//! * Optional abbreviation of option codes to a unique prefix (eg. `--verb` for `--verbose`)
+//! * Fallible construction of regex filters from patterns, with regex options (multi-line, unicode, size limits)
+//! * Glob patterns (eg. `*.txt`, `log-??`, `[a-c]*`) as an alternative to regexes in matcher filters
+//! * Regex capture groups from matched option codes, option values and parameters exposed on parsed arguments
+//! * Validation of matchers to detect misconfiguration such as duplicate names and unreachable option matchers
+//! * Optional precompiled matcher index for fast matching when a parser has many matchers
+//! * Visitor based parsing which passes each argument to callbacks as it is parsed, with early abort
+//! * Lazy iteration over the arguments in a line, parsing only as far as needed
+//! * Streaming parse of commands read from an input, with line and column numbers in errors
+//! * Reusable parse sessions which keep buffers between parses for high volume parsing
+//! * Parsed argument text borrows from the parsed line where no unescaping was needed, avoiding allocations
+//! * Fallback values for missing arguments from environment variables, configuration files or matcher defaults
+//! * Optional expansion of variables (`$VAR`, `${VAR}`, `${VAR:-default}`, `%VAR%`) in parameters and option values with a pluggable resolver
+//! * Split a command line into lexical tokens with positions, without matching
+//! * Format parsed arguments as a canonical command line (aliases resolved, consistent quoting, sensitive values redacted)
 
 
 
@@ -518,6 +550,19 @@ mod regex_or_text;
 mod matcher;
 mod arg;
 mod parser;
+mod line_formatter;
+mod span;
+mod termination;
+mod command;
+mod variable;
+mod config_file;
+mod matcher_index;
+mod tokenizer;
+mod parse_session;
+mod command_reader;
+mod arg_visitor;
+mod arg_iter;
+mod validation;
 
 mod parse_state;
 
@@ -527,10 +572,13 @@ pub use parse_error_type_id::{
 
 pub use parse_error::{
     ParseError,
+    ParseErrorContext,
 };
 
 pub use regex_or_text::{
     RegexOrText,
+    RegexOptions,
+    RegexCaptures,
 };
 
 pub use matcher:: {
@@ -538,8 +586,12 @@ pub use matcher:: {
     Matchers,
     DefaultTagType,
     OptionHasValue,
+    OptionValueArity,
+    MapDuplicates,
     MatchArgTypeId,
     DEFAULT_OPTION_HAS_VALUE,
+    DEFAULT_OPTION_VALUE_ARITY,
+    DEFAULT_MAP_DUPLICATES,
 };
 
 pub use arg::{
@@ -547,6 +599,7 @@ pub use arg::{
     BinaryProperties,
     OptionProperties,
     ParamProperties,
+    ValueSource,
     Arg,
     Args,
 };
@@ -567,6 +620,10 @@ pub use parser::{
     DEFAULT_LINE_ESCAPEABLE_CHARS,
     DEFAULT_LINE_PARSE_TERMINATE_CHARS,
     DEFAULT_LINE_FIRST_ARG_IS_BINARY,
+    DEFAULT_LINE_COMMAND_SEPARATORS,
+    DEFAULT_LINE_VARIABLE_SYNTAXES,
+    DEFAULT_LINE_VARIABLE_LITERAL_QUOTE_CHARS,
+    DEFAULT_LINE_OPTION_CODE_ABBREVIATIONS,
     DEFAULT_ENV_ARGS_QUOTE_CHARS,
     DEFAULT_ENV_ARGS_OPTION_ANNOUNCER_CHARS,
     DEFAULT_ENV_ARGS_OPTION_CODES_CASE_SENSITIVE,
@@ -581,4 +638,70 @@ pub use parser::{
     DEFAULT_ENV_ARGS_ESCAPEABLE_CHARS,
     DEFAULT_ENV_ARGS_PARSE_TERMINATE_CHARS,
     DEFAULT_ENV_ARGS_FIRST_ARG_IS_BINARY,
+    DEFAULT_ENV_ARGS_COMMAND_SEPARATORS,
+    DEFAULT_ENV_ARGS_VARIABLE_SYNTAXES,
+    DEFAULT_ENV_ARGS_VARIABLE_LITERAL_QUOTE_CHARS,
+    DEFAULT_ENV_ARGS_OPTION_CODE_ABBREVIATIONS,
+};
+
+pub use span::{
+    Span,
+};
+
+pub use termination::{
+    Termination,
+};
+
+pub use variable::{
+    VariableSyntax,
+    VariableResolver,
+    EnvVariableResolver,
+};
+
+pub use config_file::{
+    ConfigFile,
+    ConfigEntry,
+    ConfigLocation,
+    ConfigFileError,
+};
+
+pub use command::{
+    Command,
+};
+
+pub use arg_visitor::{
+    ArgVisitor,
+};
+
+pub use arg_iter::{
+    ArgIter,
+};
+
+pub use validation::{
+    ValidationIssue,
+    ValidationIssueTypeId,
+    ValidationSeverity,
+};
+
+pub use command_reader::{
+    CommandReader,
+    ReadCommand,
+    ReadCommandError,
+};
+
+pub use parse_session::{
+    ParseSession,
+};
+
+pub use tokenizer::{
+    Tokenizer,
+    Token,
+    TokenKind,
+};
+
+pub use line_formatter::{
+    LineFormatter,
+    DEFAULT_FORMATTER_ALWAYS_QUOTE_VALUES,
+    DEFAULT_FORMATTER_REDACT_VALUES,
+    DEFAULT_FORMATTER_REDACTION_TEXT,
 };
@@ -0,0 +1,433 @@
+use crate::arg::{Arg};
+use crate::matcher::{OptionValueArity};
+use crate::parser::{
+    Parser,
+    DEFAULT_LINE_QUOTE_CHARS,
+    DEFAULT_LINE_OPTION_ANNOUNCER_CHARS,
+    DEFAULT_LINE_MULTI_CHAR_OPTION_CODE_REQUIRES_DOUBLE_ANNOUNCER,
+    DEFAULT_LINE_OPTION_VALUE_ANNOUNCER_CHARS,
+    DEFAULT_LINE_EMBED_QUOTE_CHAR_WITH_DOUBLE,
+    DEFAULT_LINE_ESCAPE_CHAR,
+    DEFAULT_LINE_PARSE_TERMINATE_CHARS,
+    DEFAULT_LINE_COMMAND_SEPARATORS,
+};
+
+/// Default [always quote values](LineFormatter::always_quote_values) for a [LineFormatter].
+pub const DEFAULT_FORMATTER_ALWAYS_QUOTE_VALUES: bool = false;
+/// Default [redact values](LineFormatter::redact_values) for a [LineFormatter].
+pub const DEFAULT_FORMATTER_REDACT_VALUES: bool = true;
+/// Default [redaction text](LineFormatter::redaction_text) for a [LineFormatter].
+pub const DEFAULT_FORMATTER_REDACTION_TEXT: &str = "***";
+
+/// Renders [parsed arguments](crate::Args) as a canonical command line.
+///
+/// This is useful for logging a normalised form of what a user entered. When formatting:
+/// * Option codes are replaced with the [first text option code](crate::Matcher::first_text_option_code) of the matcher
+///   which matched the option.  This resolves aliases.  If the matcher has no text option codes, the parsed code is used.
+///   If the matcher has [negating announcer characters](crate::Matcher::negating_announcer_chars), the option is announced with
+///   the announcer character which was parsed. Otherwise negated options are formatted with the matcher's
+///   [negation prefix](crate::Matcher::negation_prefix).
+/// * Option codes are announced with the [option_announcer_char](Self::option_announcer_char). If
+///   [multi_char_option_code_requires_double_announcer](Self::multi_char_option_code_requires_double_announcer) is true,
+///   codes longer than one character are announced with 2 announcer characters.
+/// * [Counted](crate::Matcher::counted) options are repeated [count](crate::OptionProperties::count) times.
+/// * Option values are announced with the [option_value_announcer_char](Self::option_value_announcer_char).
+/// * Parameters and option values are quoted with the [quote_char](Self::quote_char) if they need quoting (or always
+///   if [always_quote_values](Self::always_quote_values) is true).  A value needs quoting if it is empty, contains whitespace
+///   or one of the [special_chars](Self::special_chars), or starts with one of the
+///   [leading_special_chars](Self::leading_special_chars).
+/// * Values of arguments whose matcher has [redact_value](crate::Matcher::redact_value) set are replaced with
+///   [redaction_text](Self::redaction_text) if [redact_values](Self::redact_values) is true.
+///
+/// The [new](Self::new) constructor creates a formatter whose style corresponds to the [Parser](crate::Parser) line defaults.
+/// Use [with_parser_style](Self::with_parser_style) to create a formatter whose style matches an existing parser so that the
+/// formatted line can be parsed again by that parser.
+#[derive(Debug, Clone)]
+pub struct LineFormatter {
+    quote_char: Option<char>,
+    option_announcer_char: char,
+    multi_char_option_code_requires_double_announcer: bool,
+    option_value_announcer_char: char,
+    embed_quote_char_with_double: bool,
+    escape_char: Option<char>,
+    special_chars: Vec<char>,
+    leading_special_chars: Vec<char>,
+    always_quote_values: bool,
+    redact_values: bool,
+    redaction_text: String,
+}
+
+impl LineFormatter {
+    /// Create a new LineFormatter with a style matching the [Parser](crate::Parser) line defaults.
+    pub fn new() -> Self {
+        LineFormatter {
+            quote_char: DEFAULT_LINE_QUOTE_CHARS.first().copied(),
+            option_announcer_char: DEFAULT_LINE_OPTION_ANNOUNCER_CHARS[0],
+            multi_char_option_code_requires_double_announcer: DEFAULT_LINE_MULTI_CHAR_OPTION_CODE_REQUIRES_DOUBLE_ANNOUNCER,
+            option_value_announcer_char: DEFAULT_LINE_OPTION_VALUE_ANNOUNCER_CHARS[0],
+            embed_quote_char_with_double: DEFAULT_LINE_EMBED_QUOTE_CHAR_WITH_DOUBLE,
+            escape_char: DEFAULT_LINE_ESCAPE_CHAR,
+            special_chars: special_chars(&DEFAULT_LINE_QUOTE_CHARS, &DEFAULT_LINE_PARSE_TERMINATE_CHARS,
+                DEFAULT_LINE_COMMAND_SEPARATORS.iter().copied()),
+            leading_special_chars: DEFAULT_LINE_OPTION_ANNOUNCER_CHARS.to_vec(),
+            always_quote_values: DEFAULT_FORMATTER_ALWAYS_QUOTE_VALUES,
+            redact_values: DEFAULT_FORMATTER_REDACT_VALUES,
+            redaction_text: String::from(DEFAULT_FORMATTER_REDACTION_TEXT),
+        }
+    }
+
+    /// Create a new LineFormatter with a style matching the style of a [Parser](crate::Parser).  Where the parser supports
+    /// several characters for a purpose (eg. [quote_chars](crate::Parser::quote_chars)), the first is used.
+    pub fn with_parser_style<O: Default, P: Default>(parser: &Parser<O, P>) -> Self {
+        let mut formatter = LineFormatter::new();
+        formatter.quote_char = parser.quote_chars().first().copied();
+        if let Some(option_announcer_char) = parser.option_announcer_chars().first() {
+            formatter.option_announcer_char = *option_announcer_char;
+        }
+        formatter.multi_char_option_code_requires_double_announcer = parser.multi_char_option_code_requires_double_announcer();
+        if let Some(option_value_announcer_char) = parser.option_value_announcer_chars().first() {
+            formatter.option_value_announcer_char = *option_value_announcer_char;
+        }
+        formatter.embed_quote_char_with_double = parser.embed_quote_char_with_double();
+        formatter.escape_char = *parser.escape_char();
+        formatter.special_chars = special_chars(parser.quote_chars(), parser.parse_terminate_chars(),
+            parser.command_separators().iter().map(String::as_str));
+        formatter.leading_special_chars = parser.option_announcer_chars().to_vec();
+        formatter
+    }
+}
+
+/// The characters in quote characters, parse terminate characters and command separators.
+fn special_chars<'s>(quote_chars: &[char], parse_terminate_chars: &[char], command_separators: impl Iterator<Item = &'s str>) -> Vec<char> {
+    let mut chars: Vec<char> = quote_chars.iter().chain(parse_terminate_chars.iter()).copied().collect();
+    for separator_char in command_separators.flat_map(|separator| separator.chars()) {
+        if !chars.contains(&separator_char) {
+            chars.push(separator_char);
+        }
+    }
+    chars
+}
+
+impl Default for LineFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LineFormatter {
+    /// The character used to quote parameters and option values. If `None`, values are never quoted.
+    ///
+    /// Default: `Some('"')`
+    pub fn quote_char(&self) -> Option<char> {
+        self.quote_char
+    }
+
+    /// Set [quote_char](Self::quote_char).
+    pub fn set_quote_char(&mut self, value: Option<char>) -> &mut Self {
+        self.quote_char = value;
+        self
+    }
+
+    /// The character used to announce options.
+    ///
+    /// Default: `'-'`
+    pub fn option_announcer_char(&self) -> char {
+        self.option_announcer_char
+    }
+
+    /// Set [option_announcer_char](Self::option_announcer_char).
+    pub fn set_option_announcer_char(&mut self, value: char) -> &mut Self {
+        self.option_announcer_char = value;
+        self
+    }
+
+    /// Specifies whether option codes with more than one character are announced with 2 option announcer characters.
+    ///
+    /// Default: false
+    pub fn multi_char_option_code_requires_double_announcer(&self) -> bool {
+        self.multi_char_option_code_requires_double_announcer
+    }
+
+    /// Set [multi_char_option_code_requires_double_announcer](Self::multi_char_option_code_requires_double_announcer).
+    pub fn set_multi_char_option_code_requires_double_announcer(&mut self, value: bool) -> &mut Self {
+        self.multi_char_option_code_requires_double_announcer = value;
+        self
+    }
+
+    /// The character placed between an option code and its value.
+    ///
+    /// Default: `' '`
+    pub fn option_value_announcer_char(&self) -> char {
+        self.option_value_announcer_char
+    }
+
+    /// Set [option_value_announcer_char](Self::option_value_announcer_char).
+    pub fn set_option_value_announcer_char(&mut self, value: char) -> &mut Self {
+        self.option_value_announcer_char = value;
+        self
+    }
+
+    /// Specifies whether a quote character within a quoted value is embedded by doubling it.  If false, the quote character is
+    /// embedded by preceding it with the [escape_char](Self::escape_char) (if any).
+    ///
+    /// Default: true
+    pub fn embed_quote_char_with_double(&self) -> bool {
+        self.embed_quote_char_with_double
+    }
+
+    /// Set [embed_quote_char_with_double](Self::embed_quote_char_with_double).
+    pub fn set_embed_quote_char_with_double(&mut self, value: bool) -> &mut Self {
+        self.embed_quote_char_with_double = value;
+        self
+    }
+
+    /// The escape character. If `Some`, escape characters in values are escaped. Quote characters in quoted values are
+    /// also escaped if [embed_quote_char_with_double](Self::embed_quote_char_with_double) is false.
+    ///
+    /// Default: `None`
+    pub fn escape_char(&self) -> Option<char> {
+        self.escape_char
+    }
+
+    /// Set [escape_char](Self::escape_char).
+    pub fn set_escape_char(&mut self, value: Option<char>) -> &mut Self {
+        self.escape_char = value;
+        self
+    }
+
+    /// Characters which cause a parameter or option value to be quoted if they occur anywhere in the value.
+    ///
+    /// [new](Self::new) sets these to the characters in the [Parser](crate::Parser) line default quote characters, parse terminate
+    /// characters and command separators.  [with_parser_style](Self::with_parser_style) sets them to the characters in the
+    /// parser's [quote_chars](crate::Parser::quote_chars), [parse_terminate_chars](crate::Parser::parse_terminate_chars) and
+    /// [command_separators](crate::Parser::command_separators).
+    pub fn special_chars(&self) -> &[char] {
+        &self.special_chars
+    }
+
+    /// Set [special_chars](Self::special_chars).
+    pub fn set_special_chars(&mut self, value: &[char]) -> &mut Self {
+        self.special_chars = Vec::from(value);
+        self
+    }
+
+    /// Characters which cause a parameter or option value to be quoted if the value starts with them.
+    ///
+    /// [new](Self::new) sets these to the [Parser](crate::Parser) line default option announcer characters.
+    /// [with_parser_style](Self::with_parser_style) sets them to the parser's
+    /// [option_announcer_chars](crate::Parser::option_announcer_chars).
+    pub fn leading_special_chars(&self) -> &[char] {
+        &self.leading_special_chars
+    }
+
+    /// Set [leading_special_chars](Self::leading_special_chars).
+    pub fn set_leading_special_chars(&mut self, value: &[char]) -> &mut Self {
+        self.leading_special_chars = Vec::from(value);
+        self
+    }
+
+    /// Specifies whether all parameters and option values are quoted.  If false, only values which need quoting are quoted.
+    /// That is, values which are empty, contain whitespace, the [quote_char](Self::quote_char) or one of the
+    /// [special_chars](Self::special_chars), or start with one of the [leading_special_chars](Self::leading_special_chars).
+    ///
+    /// Default: false
+    pub fn always_quote_values(&self) -> bool {
+        self.always_quote_values
+    }
+
+    /// Set [always_quote_values](Self::always_quote_values).
+    pub fn set_always_quote_values(&mut self, value: bool) -> &mut Self {
+        self.always_quote_values = value;
+        self
+    }
+
+    /// Specifies whether the values of arguments matched by a matcher with [redact_value](crate::Matcher::redact_value)
+    /// set, are replaced with [redaction_text](Self::redaction_text).
+    ///
+    /// Default: true
+    pub fn redact_values(&self) -> bool {
+        self.redact_values
+    }
+
+    /// Set [redact_values](Self::redact_values).
+    pub fn set_redact_values(&mut self, value: bool) -> &mut Self {
+        self.redact_values = value;
+        self
+    }
+
+    /// The text which replaces redacted values.
+    ///
+    /// Default: `"***"`
+    pub fn redaction_text(&self) -> &str {
+        &self.redaction_text
+    }
+
+    /// Set [redaction_text](Self::redaction_text).
+    pub fn set_redaction_text(&mut self, value: &str) -> &mut Self {
+        self.redaction_text = String::from(value);
+        self
+    }
+}
+
+impl LineFormatter {
+    /// Format an array of parsed arguments as a canonical command line. The arguments are separated by a space character.
+    pub fn format_args<O: Default, P: Default>(&self, args: &[Arg<O, P>]) -> String {
+        let mut line = String::with_capacity(args.len() * 16);
+        for arg in args {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            self.push_arg(&mut line, arg);
+        }
+        line
+    }
+
+    /// Format one parsed argument in canonical form.
+    pub fn format_arg<O: Default, P: Default>(&self, arg: &Arg<O, P>) -> String {
+        let mut text = String::with_capacity(16);
+        self.push_arg(&mut text, arg);
+        text
+    }
+
+    /// Format a parameter or option value, quoting and escaping it if necessary.
+    pub fn format_value(&self, value: &str) -> String {
+        let mut text = String::with_capacity(value.len() + 2);
+        self.push_value(&mut text, value);
+        text
+    }
+
+    fn push_arg<O: Default, P: Default>(&self, text: &mut String, arg: &Arg<O, P>) {
+        match arg {
+            Arg::Binary(properties) => {
+                self.push_value(text, &properties.value_text);
+            }
+            Arg::Param(properties) => {
+                if self.redact_values && properties.matcher.redact_value() {
+                    text.push_str(&self.redaction_text);
+                } else {
+                    self.push_value(text, &properties.value_text);
+                }
+            }
+            Arg::Option(properties) => {
+                let option_start_idx = text.len();
+                let mut code = String::from(properties.matcher.first_text_option_code().unwrap_or(&properties.code));
+                let announcer_char = if properties.matcher.negating_announcer_chars().is_empty() {
+                    self.option_announcer_char
+                } else {
+                    // announcer specifies whether option is enabled
+                    properties.announcer_char
+                };
+                if !properties.enabled && !properties.matcher.negating_announcer_chars().contains(&announcer_char) {
+                    if let Some(negation_prefix) = properties.matcher.negation_prefix() {
+                        code.insert_str(0, negation_prefix);
+                    }
+                }
+                text.push(announcer_char);
+                if self.multi_char_option_code_requires_double_announcer && code.chars().nth(1).is_some() {
+                    text.push(announcer_char);
+                }
+                text.push_str(&code);
+
+                if let Some(value_text) = properties.value_text.as_ref() {
+                    text.push(self.option_value_announcer_char);
+                    if self.redact_values && properties.matcher.redact_value() {
+                        text.push_str(&self.redaction_text);
+                    } else {
+                        if *properties.matcher.option_value_arity() != OptionValueArity::Single && !properties.values.is_empty() {
+                            // each value is a separate argument
+                            for (value_idx, value) in properties.values.iter().enumerate() {
+                                if value_idx > 0 {
+                                    text.push(' ');
+                                }
+                                self.push_value(text, value);
+                            }
+                        } else {
+                            match properties.matcher.value_delimiter() {
+                                Some(delimiter) if !properties.values.is_empty() => {
+                                    for (value_idx, value) in properties.values.iter().enumerate() {
+                                        if value_idx > 0 {
+                                            text.push(*delimiter);
+                                        }
+                                        self.push_delimited_value(text, value, *delimiter);
+                                    }
+                                }
+                                _ => self.push_value(text, value_text),
+                            }
+                        }
+                    }
+                }
+
+                // counted options are repeated
+                if properties.count > 1 {
+                    let option_text = String::from(&text[option_start_idx..]);
+                    for _ in 1..properties.count {
+                        text.push(' ');
+                        text.push_str(&option_text);
+                    }
+                }
+            }
+        }
+    }
+
+    fn push_delimited_value(&self, text: &mut String, value: &str, delimiter: char) {
+        // delimiter within an element must be quoted
+        let optioned_quote_char = if self.always_quote_values || self.value_needs_quoting(value) || value.contains(delimiter) {
+            self.quote_char
+        } else {
+            None
+        };
+        self.push_optionally_quoted_value(text, value, optioned_quote_char);
+    }
+
+    fn push_value(&self, text: &mut String, value: &str) {
+        let optioned_quote_char = if self.always_quote_values || self.value_needs_quoting(value) {
+            self.quote_char
+        } else {
+            None
+        };
+        self.push_optionally_quoted_value(text, value, optioned_quote_char);
+    }
+
+    fn push_optionally_quoted_value(&self, text: &mut String, value: &str, optioned_quote_char: Option<char>) {
+        if let Some(quote_char) = optioned_quote_char {
+            text.push(quote_char);
+            for char in value.chars() {
+                if char == quote_char {
+                    if self.embed_quote_char_with_double {
+                        text.push(quote_char);
+                    } else {
+                        if let Some(escape_char) = self.escape_char {
+                            text.push(escape_char);
+                        }
+                    }
+                } else {
+                    if Some(char) == self.escape_char {
+                        text.push(char);
+                    }
+                }
+                text.push(char);
+            }
+            text.push(quote_char);
+        } else {
+            for char in value.chars() {
+                if Some(char) == self.escape_char {
+                    text.push(char);
+                }
+                text.push(char);
+            }
+        }
+    }
+
+    fn value_needs_quoting(&self, value: &str) -> bool {
+        match value.chars().next() {
+            None => true,
+            Some(first_char) => {
+                first_char == self.option_announcer_char
+                ||
+                self.leading_special_chars.contains(&first_char)
+                ||
+                value.chars().any(|char| char.is_whitespace() || Some(char) == self.quote_char || self.special_chars.contains(&char))
+            }
+        }
+    }
+}
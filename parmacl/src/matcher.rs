@@ -24,6 +24,37 @@ pub enum OptionHasValue {
     Never,
 }
 
+/// Specifies how many value arguments an option with a value has. See [Matcher::option_value_arity].
+#[derive(Debug, PartialEq, Eq)]
+pub enum OptionValueArity {
+    /// Option has one value argument.
+    Single,
+    /// Option has exactly the specified number of value arguments (eg. `--point 1 2 3`).  The value arguments following the first
+    /// are the next parameter arguments.  `Exactly(0)` means the option never has a value, as if
+    /// [option_has_value](Matcher::option_has_value) were [Never](OptionHasValue::Never).
+    Exactly(usize),
+    /// Option has one or more value arguments. All parameter arguments following the option, up to the next option, are values
+    /// of the option (eg. `--files x y z`).
+    UntilNextOption,
+}
+
+/// Default value of assigned to [Matcher::option_value_arity] when a new [Matcher](Matcher) is created.
+pub const DEFAULT_OPTION_VALUE_ARITY: OptionValueArity = OptionValueArity::Single;
+
+/// Specifies how duplicate keys in map options are handled. See [Matcher::map_duplicates].
+#[derive(Debug, PartialEq, Eq)]
+pub enum MapDuplicates {
+    /// The last pair with a key is kept. Earlier pairs with the same key are removed.
+    LastWins,
+    /// A duplicate key is a [DuplicateMapKey](crate::ParseErrorTypeId::DuplicateMapKey) error.
+    Error,
+    /// All pairs are kept.
+    Collect,
+}
+
+/// Default value of assigned to [Matcher::map_duplicates] when a new [Matcher](Matcher) is created.
+pub const DEFAULT_MAP_DUPLICATES: MapDuplicates = MapDuplicates::LastWins;
+
 /// Specifies whether an argument is an option or a parameter.
 #[derive(Debug, PartialEq, Eq)]
 pub enum MatchArgTypeId {
@@ -48,9 +79,21 @@ pub const DEFAULT_OPTION_HAS_VALUE: OptionHasValue = OptionHasValue::Never;
 /// * Option code: [option_codes](Self::option_codes)
 /// * Whether an option has a value: [option_has_value](Self::option_has_value) 
 /// * Whether an option value can start with an [option announcer character](crate::Parser::option_announcer_chars): 
-/// [option_value_can_start_with_option_announcer](Self::option_value_can_start_with_option_announcer)
+///   [option_value_can_start_with_option_announcer](Self::option_value_can_start_with_option_announcer)
 /// * Option value text: [value_text](Self::value_text)
 /// 
+/// An option can have a list of values, either delimited within one value ([value_delimiter](Self::value_delimiter)) or as
+/// several value arguments ([option_value_arity](Self::option_value_arity)).  An option can also hold key/value pairs
+/// ([map_separator](Self::map_separator)).
+/// 
+/// Options matched by a matcher can be negated (eg. `--no-color` or `-x` where `+x` enables) with
+/// [negation_prefix](Self::negation_prefix) and [negating_announcer_chars](Self::negating_announcer_chars).
+/// 
+/// A matcher can also specify a fallback value for an argument which is not present in a command line. The fallback is taken
+/// from an environment variable ([env_var_name](Self::env_var_name)), a [configuration file](crate::Parser::config_files) or a
+/// default ([default_value](Self::default_value)). See
+/// [env_var_name](Self::env_var_name) for details.
+/// 
 /// Before parsing a command line, the [Parser](crate::Parser) instance should be assigned one or more `Matcher` instance. When a command line
 /// is parsed, all arguments must be matched by a matcher. If zero matchers are assigned to a Parser, then all arguments will be matched.
 /// 
@@ -79,6 +122,18 @@ pub struct Matcher<O: Default = DefaultTagType, P: Default = DefaultTagType> {
     param_indices: Option<Vec<usize>>,
     /// Optionally specifies the text an option value or parameter needs to be equal to.
     value_text: Option<RegexOrText>,
+    redact_value: bool,
+    env_var_name: Option<String>,
+    default_value: Option<String>,
+    negation_prefix: Option<String>,
+    negating_announcer_chars: Vec<char>,
+    counted: bool,
+    value_delimiter: Option<char>,
+    option_value_arity: OptionValueArity,
+    map_separator: Option<char>,
+    map_key: Option<RegexOrText>,
+    map_duplicates: MapDuplicates,
+    choices: Vec<String>,
 }
 
 impl<O: Default, P: Default> Matcher<O, P> {
@@ -332,6 +387,13 @@ impl<O: Default, P: Default> Matcher<O, P> {
         self
     }
 
+    /// Get the text of the first [option code](Self::option_codes) which matches with text (not regex).
+    /// 
+    /// This is considered the canonical code of the option. Returns `None` if no option codes match with text.
+    pub fn first_text_option_code(&self) -> Option<&str> {
+        self.option_codes_as_slice().iter().find(|code| !code.is_regex()).map(|code| code.text())
+    }
+
     /// Match Filter: Accepts an option argument with or without an option value according to the
     /// [OptionHasValue](OptionHasValue) variant. (Default: [Never](OptionHasValue::Never))
     pub fn option_has_value(&self) -> &OptionHasValue {
@@ -344,6 +406,16 @@ impl<O: Default, P: Default> Matcher<O, P> {
         self
     }
 
+    /// [option_has_value](Self::option_has_value) taking into account that an
+    /// [option_value_arity](Self::option_value_arity) of `Exactly(0)` means the option never has a value.
+    pub(crate) fn effective_option_has_value(&self) -> &OptionHasValue {
+        if self.option_value_arity == OptionValueArity::Exactly(0) {
+            &OptionHasValue::Never
+        } else {
+            &self.option_has_value
+        }
+    }
+
     /// Match Filter: Specifies whether an option argument with a value which starts with an
     /// [option announcer character](crate::Parser::option_announcer_chars) is accepted.
     /// 
@@ -392,6 +464,299 @@ impl<O: Default, P: Default> Matcher<O, P> {
         self.value_text = None;
         self
     }
+
+    /// Specifies whether the values of arguments matched by this matcher are sensitive (for example, passwords).
+    /// 
+    /// This is not used as a match filter.  A [LineFormatter](crate::LineFormatter) will replace these values with its
+    /// [redaction_text](crate::LineFormatter::redaction_text) if its [redact_values](crate::LineFormatter::redact_values)
+    /// property is true. (Default: false)
+    pub fn redact_value(&self) -> bool {
+        self.redact_value
+    }
+
+    /// Set [redact_value](Self::redact_value).
+    pub fn set_redact_value(&mut self, value: bool) -> &mut Self {
+        self.redact_value = value;
+        self
+    }
+
+    /// The name of an environment variable which supplies a value if no argument in the command line was matched by this matcher.
+    /// 
+    /// After a command line has been parsed, an argument is added for each matcher which did not match any argument and which
+    /// has either an environment variable that can be resolved, a value in one of the Parser's
+    /// [config_files](crate::Parser::config_files) or a [default_value](Self::default_value).  The environment variable
+    /// takes precedence over configuration files which take precedence over the default value.  Environment variables are
+    /// resolved with the Parser's [env_var_resolver](crate::Parser::env_var_resolver).  The added argument is a parameter if the matcher's
+    /// [arg_type](Self::arg_type) is `Param` (or it is `None` and the matcher has no [option_codes](Self::option_codes)).
+    /// Otherwise it is an option whose code is the [first text option code](Self::first_text_option_code).  The argument's
+    /// `value_source` property specifies where its value came from.
+    pub fn env_var_name(&self) -> &Option<String> {
+        &self.env_var_name
+    }
+
+    /// Set [env_var_name](Self::env_var_name) as an Option.
+    pub fn set_env_var_name(&mut self, value: Option<String>) -> &mut Self {
+        self.env_var_name = value;
+        self
+    }
+
+    /// Set [env_var_name](Self::env_var_name).
+    pub fn some_env_var_name(&mut self, value: &str) -> &mut Self {
+        self.env_var_name = Some(String::from(value));
+        self
+    }
+
+    /// Clear [env_var_name](Self::env_var_name).
+    pub fn none_env_var_name(&mut self) -> &mut Self {
+        self.env_var_name = None;
+        self
+    }
+
+    /// The value used if no argument in the command line was matched by this matcher, the [environment
+    /// variable](Self::env_var_name) (if any) cannot be resolved and no [configuration file](crate::Parser::config_files) has
+    /// a value for the matcher.
+    pub fn default_value(&self) -> &Option<String> {
+        &self.default_value
+    }
+
+    /// Set [default_value](Self::default_value) as an Option.
+    pub fn set_default_value(&mut self, value: Option<String>) -> &mut Self {
+        self.default_value = value;
+        self
+    }
+
+    /// Set [default_value](Self::default_value).
+    pub fn some_default_value(&mut self, value: &str) -> &mut Self {
+        self.default_value = Some(String::from(value));
+        self
+    }
+
+    /// Clear [default_value](Self::default_value).
+    pub fn none_default_value(&mut self) -> &mut Self {
+        self.default_value = None;
+        self
+    }
+
+    /// A prefix which negates option codes matched by this matcher.  For example, with a prefix of `no-`, a matcher with code
+    /// `color` will also match `--no-color`.  Options matched with the prefix have [enabled](crate::OptionProperties::enabled)
+    /// set to false.  The prefix is compared according to [Parser.option_codes_case_sensitive](crate::Parser::option_codes_case_sensitive).
+    /// An option code which directly matches one of the matcher's [option_codes](Self::option_codes) is not treated as negated.
+    /// Only has effect if the matcher has option codes. (Default: None)
+    pub fn negation_prefix(&self) -> &Option<String> {
+        &self.negation_prefix
+    }
+
+    /// If `code` starts with the [negation_prefix](Self::negation_prefix) (and has more after it), returns the rest of the code.
+    pub(crate) fn strip_negation_prefix<'c>(&self, code: &'c str, case_sensitive: bool) -> Option<&'c str> {
+        match &self.negation_prefix {
+            Some(prefix) if !prefix.is_empty() && code.len() > prefix.len() && code.is_char_boundary(prefix.len()) => {
+                let (code_prefix, stripped_code) = code.split_at(prefix.len());
+                let prefix_matches = if case_sensitive {
+                    code_prefix == prefix
+                } else {
+                    code_prefix.to_uppercase() == prefix.to_uppercase()
+                };
+                if prefix_matches {
+                    Some(stripped_code)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Set [negation_prefix](Self::negation_prefix) as an Option.
+    pub fn set_negation_prefix(&mut self, value: Option<String>) -> &mut Self {
+        self.negation_prefix = value;
+        self
+    }
+
+    /// Set [negation_prefix](Self::negation_prefix).
+    pub fn some_negation_prefix(&mut self, value: &str) -> &mut Self {
+        self.negation_prefix = Some(String::from(value));
+        self
+    }
+
+    /// Clear [negation_prefix](Self::negation_prefix).
+    pub fn none_negation_prefix(&mut self) -> &mut Self {
+        self.negation_prefix = None;
+        self
+    }
+
+    /// [Option announcer characters](crate::Parser::option_announcer_chars) which negate options matched by this matcher.
+    /// For example, if the Parser's option announcer characters are `['+', '-']` and this is `['-']`, then `+x` enables and
+    /// `-x` disables an option.  Options announced with one of these characters have [enabled](crate::OptionProperties::enabled)
+    /// set to false. (Default: `[]`)
+    pub fn negating_announcer_chars(&self) -> &[char] {
+        &self.negating_announcer_chars
+    }
+
+    /// Set [negating_announcer_chars](Self::negating_announcer_chars).
+    pub fn set_negating_announcer_chars(&mut self, value: &[char]) -> &mut Self {
+        self.negating_announcer_chars = Vec::from(value);
+        self
+    }
+
+    /// Specifies whether options matched by this matcher are counted (eg. verbosity flags).
+    /// 
+    /// If true, all occurrences of the option in a command line are merged into the first occurrence and the total number of
+    /// occurrences is held in [OptionProperties.count](crate::OptionProperties::count).  Occurrences can be separate
+    /// (`-v -v -v`) or clustered (`-vvv`).  A clustered occurrence is one of the matcher's text [option_codes](Self::option_codes)
+    /// repeated and does not require a double announcer when
+    /// [multi_char_option_code_requires_double_announcer](crate::Parser::multi_char_option_code_requires_double_announcer) is true.
+    /// (Default: false)
+    pub fn counted(&self) -> bool {
+        self.counted
+    }
+
+    /// Set [counted](Self::counted).
+    pub fn set_counted(&mut self, value: bool) -> &mut Self {
+        self.counted = value;
+        self
+    }
+
+    /// A character which delimits a list of values within an option value (eg. `,` for `--include a,b,c`).
+    /// 
+    /// The delimited values are held in [OptionProperties.values](crate::OptionProperties::values).  Delimiters within quotes
+    /// or which are escaped do not delimit values.  Each delimited value can be quoted (eg. `--include "a b","c,d",e`).
+    /// (Default: None)
+    pub fn value_delimiter(&self) -> &Option<char> {
+        &self.value_delimiter
+    }
+
+    /// Set [value_delimiter](Self::value_delimiter) as an Option.
+    pub fn set_value_delimiter(&mut self, value: Option<char>) -> &mut Self {
+        self.value_delimiter = value;
+        self
+    }
+
+    /// Set [value_delimiter](Self::value_delimiter).
+    pub fn some_value_delimiter(&mut self, value: char) -> &mut Self {
+        self.value_delimiter = Some(value);
+        self
+    }
+
+    /// Clear [value_delimiter](Self::value_delimiter).
+    pub fn none_value_delimiter(&mut self) -> &mut Self {
+        self.value_delimiter = None;
+        self
+    }
+
+    /// Specifies how many value arguments an option matched by this matcher has, if it has a value.
+    /// 
+    /// Additional value arguments are taken from the parameter arguments following the option's first value and are not matched
+    /// against parameter matchers.  All value arguments are held in [OptionProperties.values](crate::OptionProperties::values).
+    /// Additional value arguments are not split by the [value_delimiter](Self::value_delimiter).  If an option has fewer value
+    /// arguments than required, an [OptionMissingValues](crate::ParseErrorTypeId::OptionMissingValues) error is returned.
+    /// 
+    /// Default: [`OptionValueArity::Single`](OptionValueArity::Single)
+    pub fn option_value_arity(&self) -> &OptionValueArity {
+        &self.option_value_arity
+    }
+
+    /// Set [option_value_arity](Self::option_value_arity).
+    pub fn set_option_value_arity(&mut self, value: OptionValueArity) -> &mut Self {
+        self.option_value_arity = value;
+        self
+    }
+
+    /// If set, options matched by this matcher are map options (eg. `-D name=value` or `--set key=val`).  Each of the option's
+    /// [values](crate::OptionProperties::values) is split at the first occurrence of this separator into a key and value which are
+    /// held in [OptionProperties.pairs](crate::OptionProperties::pairs).  A value without the separator is a key with an empty
+    /// value (eg. `-DNDEBUG`).
+    /// (Default: None)
+    pub fn map_separator(&self) -> &Option<char> {
+        &self.map_separator
+    }
+
+    /// Set [map_separator](Self::map_separator) as an Option.
+    pub fn set_map_separator(&mut self, value: Option<char>) -> &mut Self {
+        self.map_separator = value;
+        self
+    }
+
+    /// Set [map_separator](Self::map_separator).
+    pub fn some_map_separator(&mut self, value: char) -> &mut Self {
+        self.map_separator = Some(value);
+        self
+    }
+
+    /// Clear [map_separator](Self::map_separator).
+    pub fn none_map_separator(&mut self) -> &mut Self {
+        self.map_separator = None;
+        self
+    }
+
+    /// If set, the keys of a map option must match this. Otherwise any non-empty key is valid.  Keys which are not valid are an
+    /// [InvalidMapKey](crate::ParseErrorTypeId::InvalidMapKey) error.  Keys are matched with
+    /// [Parser.option_values_case_sensitive](crate::Parser::option_values_case_sensitive).
+    /// (Default: None)
+    pub fn map_key(&self) -> &Option<RegexOrText> {
+        &self.map_key
+    }
+
+    /// Set [map_key](Self::map_key) as an Option.
+    pub fn set_map_key(&mut self, value: Option<RegexOrText>) -> &mut Self {
+        self.map_key = value;
+        self
+    }
+
+    /// Set [map_key](Self::map_key).
+    pub fn some_map_key(&mut self, value: RegexOrText) -> &mut Self {
+        self.map_key = Some(value);
+        self
+    }
+
+    /// Clear [map_key](Self::map_key).
+    pub fn none_map_key(&mut self) -> &mut Self {
+        self.map_key = None;
+        self
+    }
+
+    /// Specifies how a key which occurs more than once in the map options matched by this matcher is handled. This applies
+    /// across all the options matched by this matcher in a parse, as well as within one option.
+    /// 
+    /// Default: [`MapDuplicates::LastWins`](MapDuplicates::LastWins)
+    pub fn map_duplicates(&self) -> &MapDuplicates {
+        &self.map_duplicates
+    }
+
+    /// Set [map_duplicates](Self::map_duplicates).
+    pub fn set_map_duplicates(&mut self, value: MapDuplicates) -> &mut Self {
+        self.map_duplicates = value;
+        self
+    }
+
+    /// The allowed values of options matched by this matcher (eg. `["auto", "always", "never"]`).  If not empty, each of an
+    /// option's [values](crate::OptionProperties::values) must be one of these, otherwise an
+    /// [InvalidChoice](crate::ParseErrorTypeId::InvalidChoice) error is returned with the allowed values in
+    /// [candidates](crate::ParseErrorContext::candidates).
+    /// 
+    /// Values are matched with [Parser.option_values_case_sensitive](crate::Parser::option_values_case_sensitive) and replaced
+    /// with the spelling in this list.  Unlike [value_text](Self::value_text), choices are not used to select a matcher.
+    /// 
+    /// For map options (see [map_separator](Self::map_separator)), the choices apply to the value of each
+    /// [pair](crate::OptionProperties::pairs) instead of the whole option value.
+    /// (Default: `[]`)
+    pub fn choices(&self) -> &[String] {
+        &self.choices
+    }
+
+    /// Set [choices](Self::choices).
+    pub fn set_choices(&mut self, value: &[&str]) -> &mut Self {
+        self.choices = value.iter().map(|choice| String::from(*choice)).collect();
+        self
+    }
+
+    /// The [choices](Self::choices) formatted for help text (eg. `auto|always|never`). Returns `None` if there are no choices.
+    pub fn choices_help_text(&self) -> Option<String> {
+        if self.choices.is_empty() {
+            None
+        } else {
+            Some(self.choices.join("|"))
+        }
+    }
 }
 
 impl<O: Default, P: Default> Default for Matcher<O, P> {
@@ -409,7 +774,19 @@ impl<O: Default, P: Default> Default for Matcher<O, P> {
             option_has_value: DEFAULT_OPTION_HAS_VALUE,
             option_value_can_start_with_option_announcer: false,
             param_indices: None,
-            value_text: None
+            value_text: None,
+            redact_value: false,
+            env_var_name: None,
+            default_value: None,
+            negation_prefix: None,
+            negating_announcer_chars: Vec::new(),
+            counted: false,
+            value_delimiter: None,
+            option_value_arity: DEFAULT_OPTION_VALUE_ARITY,
+            map_separator: None,
+            map_key: None,
+            map_duplicates: DEFAULT_MAP_DUPLICATES,
+            choices: Vec::new(),
         }
     }
 }
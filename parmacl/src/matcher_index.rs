@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::slice;
+use regex::{RegexSet, RegexSetBuilder, SetMatchesIntoIter};
+use crate::matcher::{Matcher, MatchArgTypeId};
+use crate::regex_or_text::{RegexOrText, RegexOptions};
+
+/// Lookup tables built by [Parser.compile](crate::Parser::compile) which narrow down the matchers that need to be tried for an
+/// option code or parameter.  Lookups return a superset of the matchers which can match, in matcher order, so that the first
+/// matcher which fully matches is the same as without the index.
+pub(crate) struct MatcherIndex {
+    option: CodeIndex,
+    param: CodeIndex,
+}
+
+impl MatcherIndex {
+    pub(crate) fn new<O: Default, P: Default>(matchers: &[Matcher<O, P>], option_codes_case_sensitive: bool, params_case_sensitive: bool)
+        -> Self
+    {
+        let mut option = CodeIndexBuilder::new(option_codes_case_sensitive);
+        let mut param = CodeIndexBuilder::new(params_case_sensitive);
+
+        for (matcher_idx, matcher) in matchers.iter().enumerate() {
+            if *matcher.arg_type() != Some(MatchArgTypeId::Param) {
+                // negated and clustered codes are not indexed
+                let always = matcher.option_codes().is_none() || matcher.negation_prefix().is_some() || matcher.counted();
+                if always {
+                    option.always_matcher_idxs.push(matcher_idx);
+                } else {
+                    option.push_all(matcher.option_codes_as_slice(), matcher_idx);
+                }
+            }
+
+            if *matcher.arg_type() != Some(MatchArgTypeId::Option) {
+                match matcher.value_text() {
+                    None => param.always_matcher_idxs.push(matcher_idx),
+                    Some(value_text) => param.push_all(std::slice::from_ref(value_text), matcher_idx),
+                }
+            }
+        }
+
+        MatcherIndex {
+            option: option.build(),
+            param: param.build(),
+        }
+    }
+
+    /// Indices of the matchers which may match an option with the specified code, in ascending order.
+    pub(crate) fn option_matcher_idxs(&self, code: &str) -> MatcherIdxs<'_> {
+        self.option.matcher_idxs(code)
+    }
+
+    /// Indices of the matchers which may match a parameter with the specified value, in ascending order.
+    pub(crate) fn param_matcher_idxs(&self, value: &str) -> MatcherIdxs<'_> {
+        self.param.matcher_idxs(value)
+    }
+}
+
+struct CodeIndex {
+    case_sensitive: bool,
+    text_matcher_idxs: HashMap<String, Vec<usize>>,
+    regex_set: Option<RegexSet>,
+    regex_matcher_idxs: Vec<usize>,
+    always_matcher_idxs: Vec<usize>,
+}
+
+impl CodeIndex {
+    fn matcher_idxs(&self, text: &str) -> MatcherIdxs<'_> {
+        let text_matcher_idxs = if self.case_sensitive {
+            self.text_matcher_idxs.get(text)
+        } else {
+            self.text_matcher_idxs.get(&text.to_uppercase())
+        };
+
+        MatcherIdxs {
+            always: self.always_matcher_idxs.iter().peekable(),
+            text: text_matcher_idxs.map(|idxs| idxs.as_slice()).unwrap_or(&[]).iter().peekable(),
+            regex_patterns: self.regex_set.as_ref().map(|regex_set| regex_set.matches(text).into_iter().peekable()),
+            regex_matcher_idxs: &self.regex_matcher_idxs,
+            last_matcher_idx: None,
+        }
+    }
+}
+
+/// Iterates the matcher indices returned by a [CodeIndex] lookup.  The always, text and regex indices are each in ascending
+/// order and are merged (without duplicates) so that matchers are tried in list order and the first match wins.
+pub(crate) struct MatcherIdxs<'a> {
+    always: Peekable<slice::Iter<'a, usize>>,
+    text: Peekable<slice::Iter<'a, usize>>,
+    regex_patterns: Option<Peekable<SetMatchesIntoIter>>,
+    regex_matcher_idxs: &'a [usize],
+    last_matcher_idx: Option<usize>,
+}
+
+impl Iterator for MatcherIdxs<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            let always = self.always.peek().map(|idx| **idx);
+            let text = self.text.peek().map(|idx| **idx);
+            let regex_matcher_idxs = self.regex_matcher_idxs;
+            let regex = self.regex_patterns.as_mut()
+                .and_then(|patterns| patterns.peek().map(|pattern_idx| regex_matcher_idxs[*pattern_idx]));
+
+            let matcher_idx = [always, text, regex].into_iter().flatten().min()?;
+            if always == Some(matcher_idx) {
+                self.always.next();
+            }
+            if text == Some(matcher_idx) {
+                self.text.next();
+            }
+            if regex == Some(matcher_idx) {
+                if let Some(patterns) = self.regex_patterns.as_mut() {
+                    patterns.next();
+                }
+            }
+
+            if self.last_matcher_idx != Some(matcher_idx) {
+                self.last_matcher_idx = Some(matcher_idx);
+                return Some(matcher_idx);
+            }
+        }
+    }
+}
+
+struct CodeIndexBuilder {
+    case_sensitive: bool,
+    text_matcher_idxs: HashMap<String, Vec<usize>>,
+    regex_patterns: Vec<String>,
+    regex_matcher_idxs: Vec<usize>,
+    always_matcher_idxs: Vec<usize>,
+}
+
+impl CodeIndexBuilder {
+    fn new(case_sensitive: bool) -> Self {
+        CodeIndexBuilder {
+            case_sensitive,
+            text_matcher_idxs: HashMap::new(),
+            regex_patterns: Vec::new(),
+            regex_matcher_idxs: Vec::new(),
+            always_matcher_idxs: Vec::new(),
+        }
+    }
+
+    /// Whether a code can be looked up in the index.  Codes with their own case sensitivity are not indexed.  Regexes are only
+    /// indexed if the regex set (built from their patterns with default options) reproduces them.  A regex supplied with
+    /// [with_regex](RegexOrText::with_regex) may have been built with flags which its pattern does not include.
+    fn is_indexable(regex_or_text: &RegexOrText) -> bool {
+        if regex_or_text.override_case_sensitive().is_some() {
+            false
+        } else {
+            if regex_or_text.is_regex() {
+                regex_or_text.is_regex_built_from_pattern() && *regex_or_text.regex_options() == RegexOptions::default()
+            } else {
+                true
+            }
+        }
+    }
+
+    fn push_all(&mut self, regex_or_texts: &[RegexOrText], matcher_idx: usize) {
+        if !regex_or_texts.iter().all(Self::is_indexable) {
+            self.always_matcher_idxs.push(matcher_idx);
+        } else {
+            for regex_or_text in regex_or_texts {
+                if let Some(regex) = regex_or_text.regex() {
+                    self.regex_patterns.push(String::from(regex.as_str()));
+                    self.regex_matcher_idxs.push(matcher_idx);
+                } else {
+                    let key = if self.case_sensitive {
+                        String::from(regex_or_text.text())
+                    } else {
+                        regex_or_text.text().to_uppercase()
+                    };
+                    self.text_matcher_idxs.entry(key).or_default().push(matcher_idx);
+                }
+            }
+        }
+    }
+
+    fn build(mut self) -> CodeIndex {
+        let regex_set = if self.regex_patterns.is_empty() {
+            None
+        } else {
+            match RegexSetBuilder::new(&self.regex_patterns).case_insensitive(!self.case_sensitive).build() {
+                Ok(regex_set) => Some(regex_set),
+                Err(_) => {
+                    // fall back to trying these matchers for all codes
+                    self.always_matcher_idxs.append(&mut self.regex_matcher_idxs);
+                    self.always_matcher_idxs.sort_unstable();
+                    self.always_matcher_idxs.dedup();
+                    None
+                }
+            }
+        };
+
+        CodeIndex {
+            case_sensitive: self.case_sensitive,
+            text_matcher_idxs: self.text_matcher_idxs,
+            regex_set,
+            regex_matcher_idxs: self.regex_matcher_idxs,
+            always_matcher_idxs: self.always_matcher_idxs,
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use crate::parse_error_type_id::ParseErrorTypeId;
+use crate::span::Span;
+use crate::arg::ValueSource;
 use std::{fmt::Display, error::Error};
 
 /// Error result returned by a [Parser](crate::Parser) parse function ([parse_line](crate::Parser::parse_line),
@@ -22,10 +24,40 @@ pub struct ParseError {
     /// with an option argument, it will contain the option value so far parsed if the option has a value.  Otherwise it contains
     /// an empty string.
     pub param_value_text: String,
+    /// Further details about where and why the error occurred. These are boxed to keep `Result<_, ParseError>` small.
+    pub context: Box<ParseErrorContext>,
+}
+
+/// Further details of a [ParseError] held in its [context](ParseError::context) field.
+#[derive(Debug, Clone, Default)]
+pub struct ParseErrorContext {
+    /// The position of the argument in which the error occurred. It spans from the start of the argument to where the error was
+    /// detected. If a line is parsed, the indices are positions in the line. If environment args are parsed, the indices are
+    /// positions in the environment argument in which the argument started.
+    pub span: Span,
+    /// The text of the command line (or environment argument) within [span](Self::span), exactly as entered. That is,
+    /// including any quote and escape characters.
+    pub raw_text: String,
+    /// For an [InvalidChoice](ParseErrorTypeId::InvalidChoice) error, the allowed values. For an
+    /// [AmbiguousOption](ParseErrorTypeId::AmbiguousOption) error, the option codes which the option code abbreviates.
+    /// Otherwise empty.
+    pub candidates: Vec<String>,
+    /// If the error occurred while [reading commands](crate::Parser::read_commands), the line number (starting from 1) of the
+    /// line in the input where the error was detected. Otherwise `None`.
+    pub line_number: Option<usize>,
+    /// If the error occurred while [reading commands](crate::Parser::read_commands), the column number (starting from 1) of the
+    /// character in the line where the error was detected. Otherwise `None`.
+    pub column_number: Option<usize>,
+    /// For an [InvalidFallbackValue](ParseErrorTypeId::InvalidFallbackValue) error, where the invalid value came from. For an
+    /// [UnknownConfigKey](ParseErrorTypeId::UnknownConfigKey) error, the location of the key. Otherwise `None`.
+    pub value_source: Option<ValueSource>,
 }
 
 impl ParseError {
-    pub (crate) fn new_option(type_id: ParseErrorTypeId, line_char_idx: usize, arg_idx: usize, option_idx: usize, option_code: &str, param_value_text: &str) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub (crate) fn new_option(type_id: ParseErrorTypeId, line_char_idx: usize, span: Span, raw_text: &str, arg_idx: usize, option_idx: usize, option_code: &str,
+        param_value_text: &str
+    ) -> Self {
         Self {
             type_id,
             line_char_index: line_char_idx,
@@ -34,10 +66,15 @@ impl ParseError {
             option_code: Some(String::from(option_code)),
             param_index: None,
             param_value_text: String::from(param_value_text),
+            context: Box::new(ParseErrorContext {
+                span,
+                raw_text: String::from(raw_text),
+                ..ParseErrorContext::default()
+            }),
         }
     }
 
-    pub (crate) fn new_param(type_id: ParseErrorTypeId, line_char_idx: usize, arg_idx: usize, param_idx: usize, param_value_text: &str) -> Self {
+    pub (crate) fn new_param(type_id: ParseErrorTypeId, line_char_idx: usize, span: Span, raw_text: &str, arg_idx: usize, param_idx: usize, param_value_text: &str) -> Self {
         Self {
             type_id,
             line_char_index: line_char_idx,
@@ -46,6 +83,30 @@ impl ParseError {
             option_code: None,
             param_index: Some(param_idx),
             param_value_text: String::from(param_value_text),
+            context: Box::new(ParseErrorContext {
+                span,
+                raw_text: String::from(raw_text),
+                ..ParseErrorContext::default()
+            }),
+        }
+    }
+
+    pub (crate) fn new_config(type_id: ParseErrorTypeId, line_char_idx: usize, span: Span, arg_idx: usize, key: &str,
+        value_source: ValueSource
+    ) -> Self {
+        Self {
+            type_id,
+            line_char_index: line_char_idx,
+            arg_index: arg_idx,
+            option_index: None,
+            option_code: None,
+            param_index: None,
+            param_value_text: String::from(key),
+            context: Box::new(ParseErrorContext {
+                span,
+                value_source: Some(value_source),
+                ..ParseErrorContext::default()
+            }),
         }
     }
 }
@@ -60,6 +121,12 @@ impl Display for ParseError {
         let mut error_text = String::with_capacity(id_text.len() + 60);
         error_text.push_str(" [l:");
         error_text.push_str(&self.line_char_index.to_string());
+        if let (Some(line_number), Some(column_number)) = (self.context.line_number, self.context.column_number) {
+            error_text.push_str(" ln:");
+            error_text.push_str(&line_number.to_string());
+            error_text.push_str(" col:");
+            error_text.push_str(&column_number.to_string());
+        }
         error_text.push_str(" a:");
         error_text.push_str(&self.arg_index.to_string());
 
@@ -80,6 +147,10 @@ impl Display for ParseError {
                 error_text.push('"');
             }
         }
+        if !self.context.candidates.is_empty() {
+            error_text.push_str(" candidates:");
+            error_text.push_str(&self.context.candidates.join("|"));
+        }
         error_text.push(']');
 
         write!(f, "({})", error_text)
@@ -32,10 +32,30 @@ pub enum ParseErrorTypeId {
     OptionCodeCannotContainEscapeChar,
     /// Option requires a value but value is missing.
     OptionMissingValue,
+    /// Option has fewer values than required by its matcher's [option_value_arity](crate::Matcher::option_value_arity).
+    OptionMissingValues,
     /// Option value cannot start with option announcer character.
     OptionValueCannotStartWithOptionAnnouncer,
     /// A quoted option value is missing the closing quote character.
     OptionValueMissingClosingQuoteCharacter,
+    /// Key in a map option does not match the matcher's [map_key](crate::Matcher::map_key) or is empty.
+    InvalidMapKey,
+    /// Key occurs more than once in map options and the matcher's [map_duplicates](crate::Matcher::map_duplicates) is
+    /// [`MapDuplicates::Error`](crate::MapDuplicates::Error).
+    DuplicateMapKey,
+    /// Option value is not one of the matcher's [choices](crate::Matcher::choices).
+    InvalidChoice,
+    /// Abbreviated option code is the prefix of option codes in more than one matcher. See
+    /// [Parser.option_code_abbreviations](crate::Parser::option_code_abbreviations).
+    AmbiguousOption,
+    /// A value from a matcher's [environment variable](crate::Matcher::env_var_name), a
+    /// [configuration file](crate::Parser::config_files) or [default value](crate::Matcher::default_value) is not valid for the
+    /// matcher. [ParseErrorContext.value_source](crate::ParseErrorContext::value_source) specifies where the value came from.
+    InvalidFallbackValue,
+    /// A key in one of the parser's [configuration files](crate::Parser::config_files) is not the
+    /// [name](crate::Matcher::name) of any matcher. The error's `param_value_text` holds the key and
+    /// [ParseErrorContext.value_source](crate::ParseErrorContext::value_source) holds its location.
+    UnknownConfigKey,
     /// No match found for option argument.
     UnmatchedOption,
     /// No match found for parameter argument.
@@ -60,7 +80,14 @@ impl ParseErrorTypeId {
             ParseErrorTypeId::OptionCodeCannotContainEscapeChar => "Option code cannot contain escape character",
             ParseErrorTypeId::OptionValueCannotStartWithOptionAnnouncer => "Option value cannot start with option announcer",
             ParseErrorTypeId::OptionMissingValue => "Option missing value",
+            ParseErrorTypeId::OptionMissingValues => "Option missing values",
             ParseErrorTypeId::OptionValueMissingClosingQuoteCharacter => "Option value missing closing quote character",
+            ParseErrorTypeId::InvalidMapKey => "Invalid map key",
+            ParseErrorTypeId::DuplicateMapKey => "Duplicate map key",
+            ParseErrorTypeId::InvalidChoice => "Invalid choice",
+            ParseErrorTypeId::AmbiguousOption => "Option is ambiguous",
+            ParseErrorTypeId::InvalidFallbackValue => "Invalid fallback value",
+            ParseErrorTypeId::UnknownConfigKey => "Unknown configuration file key",
             ParseErrorTypeId::UnmatchedOption => "Option not matched",
             ParseErrorTypeId::UnmatchedParam => "Parameter not matched",
         }
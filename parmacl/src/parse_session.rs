@@ -0,0 +1,74 @@
+use std::borrow::Cow;
+use crate::arg::{Args};
+use crate::matcher::{DefaultTagType};
+use crate::parse_error::{ParseError};
+use crate::parse_state::{ParseBuffers};
+use crate::parser::{Parser};
+use crate::termination::{Termination};
+
+/// A reusable session for parsing many lines with the same [Parser].
+///
+/// Created with [Parser.new_session](crate::Parser::new_session).  The buffers used while parsing are kept between parses
+/// and the parsed arguments are written into a container supplied by the caller, so that the buffers and the container's
+/// capacity are reused.  This is useful in tight loops or when handling server requests.  Argument text which cannot be
+/// borrowed from the line (eg. text with quotes or escapes removed) is still allocated.
+///
+/// [parse_line](Self::parse_line) borrows argument text from the line, so the line must outlive the container's contents.
+/// When each line is read into the same buffer (eg. with [read_line](std::io::BufRead::read_line)), use
+/// [parse_line_owned](Self::parse_line_owned) instead.
+///
+/// A session is not thread safe. Use one session per thread.
+pub struct ParseSession<'p, O: Default = DefaultTagType, P: Default = DefaultTagType> {
+    parser: &'p Parser<O, P>,
+    buffers: ParseBuffers,
+}
+
+impl<'p, O: Default, P: Default> ParseSession<'p, O, P> {
+    /// Create a new session which parses with `parser`.
+    pub fn new(parser: &'p Parser<O, P>) -> Self {
+        ParseSession {
+            parser,
+            buffers: ParseBuffers::new(),
+        }
+    }
+
+    /// The parser used by the session.
+    pub fn parser(&self) -> &'p Parser<O, P> {
+        self.parser
+    }
+
+    /// Parse a command line into `args`.
+    ///
+    /// `args` is cleared before parsing (keeping its capacity).  If successful, `args` holds the parsed arguments the same as
+    /// would be returned by [Parser.parse_line](crate::Parser::parse_line).  Otherwise an error result is returned and the
+    /// contents of `args` are unspecified.
+    pub fn parse_line<'a>(&mut self, line: &'a str, args: &mut Args<'a, O, P>) -> Result<(), ParseError>
+    where 'p: 'a
+    {
+        self.parse_line_with_termination(line, args)?;
+        Ok(())
+    }
+
+    /// Parse a command line into `args` without borrowing from the line.
+    ///
+    /// Same as [parse_line](Self::parse_line) however all argument text in `args` is owned, so the line can be changed or
+    /// dropped while `args` is still in use.  This allows a loop to read each line into the same buffer and parse it into
+    /// the same container.
+    pub fn parse_line_owned(&mut self, line: &str, args: &mut Args<'p, O, P>) -> Result<(), ParseError> {
+        args.clear();
+        self.parser.parse_line_range_into(&mut Cow::Owned(String::from(line)), 0, 0, line.len(), &mut self.buffers, args)?;
+        Ok(())
+    }
+
+    /// Parse a command line into `args` and also return where parsing was terminated.
+    ///
+    /// Same as [parse_line](Self::parse_line) however the success result holds a [Termination](Termination) if parsing was
+    /// terminated by a [parse terminate character](crate::Parser::parse_terminate_chars).
+    pub fn parse_line_with_termination<'a>(&mut self, line: &'a str, args: &mut Args<'a, O, P>)
+        -> Result<Option<Termination>, ParseError>
+    where 'p: 'a
+    {
+        args.clear();
+        self.parser.parse_line_range_into(&mut Cow::Borrowed(line), 0, 0, line.len(), &mut self.buffers, args)
+    }
+}
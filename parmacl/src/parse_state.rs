@@ -1,5 +1,7 @@
+use std::borrow::Cow;
 use crate::parse_error::{ParseError};
 use crate::parse_error_type_id::{ParseErrorTypeId};
+use crate::span::{Span};
 
 #[derive(PartialEq, Eq)]
 pub(crate) enum ArgParseState {
@@ -20,18 +22,54 @@ pub(crate) enum OptionParseState {
     InValueEscaped,
 }
 
-pub(crate) struct ParseState {
+/// Buffers used while parsing which can be kept between parses (see [ParseSession](crate::ParseSession)) so that they
+/// do not need to be reallocated.
+#[derive(Default)]
+pub(crate) struct ParseBuffers {
+    option_code_raw_text: String,
+    option_code: String,
+    value_bldr: String,
+    value_escaped_byte_idxs: Vec<usize>,
+    value_delimiter_byte_idxs: Vec<(usize, char)>,
+    option_value_delimiters: Vec<char>,
+}
+
+impl ParseBuffers {
+    pub fn new() -> Self {
+        ParseBuffers {
+            option_code_raw_text: String::from(""),
+            option_code: String::from(""),
+            value_bldr: String::with_capacity(30),
+            value_escaped_byte_idxs: Vec::new(),
+            value_delimiter_byte_idxs: Vec::new(),
+            option_value_delimiters: Vec::new(),
+        }
+    }
+}
+
+pub(crate) struct ParseState<'l> {
     pub(crate) multi_char_option_code_requires_double_announcer: bool,
-    pub(crate) line_or_env_arg: String,
+    /// Borrowed when a line is parsed so that argument text can be borrowed from it. Owned for environment arguments.
+    pub(crate) line_or_env_arg: Cow<'l, str>,
     pub(crate) line_len: usize,
     pub(crate) arg_parse_state: ArgParseState,
     pub(crate) option_parse_state: OptionParseState,
     pub(crate) env_line_approximate_char_idx: usize,
     pub(crate) env_arg_idx: usize,
+    pub(crate) option_env_arg_idx: usize,
+    pub(crate) value_start_env_arg_idx: usize,
     pub(crate) line_or_env_arg_char_idx: usize,
+    pub(crate) line_or_env_arg_byte_idx: usize,
     pub(crate) arg_start_char_idx: usize,
+    pub(crate) arg_start_byte_idx: usize,
     pub(crate) arg_start_env_line_approximate_char_idx: usize,
-    pub(crate) option_code_start_line_char_idx: usize,
+    pub(crate) option_code_start_byte_idx: usize,
+    pub(crate) option_code_end_byte_idx: usize,
+    pub(crate) option_code_span: Span,
+    pub(crate) option_code_raw_text: String,
+    pub(crate) value_start_char_idx: usize,
+    pub(crate) value_start_byte_idx: usize,
+    pub(crate) value_start_env_line_approximate_char_idx: usize,
     pub(crate) arg_quote_char: char,
     pub(crate) option_announcer_char: char,
     pub(crate) option_code: String,
@@ -39,97 +77,299 @@ pub(crate) struct ParseState {
     pub(crate) current_option_value_may_be_param: bool,
     pub(crate) value_quoted: bool,
     pub(crate) value_bldr: String,
+    pub(crate) value_escaped_byte_idxs: Vec<usize>,
+    pub(crate) value_variables_expanded: bool,
+    pub(crate) value_delimiter_byte_idxs: Vec<(usize, char)>,
+    pub(crate) value_at_element_start: bool,
+    /// The value delimiters of the matchers which can match the current option code. Resolved when the option's value starts.
+    pub(crate) option_value_delimiters: Vec<char>,
+    pub(crate) pending_values_arg_idx: Option<usize>,
+    pub(crate) pending_values_remaining: Option<usize>,
     pub(crate) arg_count: usize,
     pub(crate) option_count: usize,
     pub(crate) param_count: usize,
     pub(crate) current_param_is_binary: bool,
+    /// If true, values which may be parameters are not matched immediately but flagged in `possible_option_value_deferred`
+    /// so that an [ArgVisitor](crate::ArgVisitor) can decide.
+    pub(crate) defer_possible_option_values: bool,
+    pub(crate) possible_option_value_deferred: bool,
 }
 
-impl ParseState {
+impl<'l> ParseState<'l> {
     pub fn new(
-        line_or_env_arg: &str,
+        line_or_env_arg: Cow<'l, str>,
+        first_arg_is_binary: bool,
+        multi_char_option_code_requires_double_announcer: bool,
+    ) -> Self {
+        Self::with_buffers(line_or_env_arg, first_arg_is_binary, multi_char_option_code_requires_double_announcer, ParseBuffers::new())
+    }
+
+    /// Create a parse state which uses the buffers from a previous parse. The buffers are cleared.
+    pub fn with_buffers(
+        line_or_env_arg: Cow<'l, str>,
         first_arg_is_binary: bool,
         multi_char_option_code_requires_double_announcer: bool,
+        mut buffers: ParseBuffers,
     ) -> Self {
+        buffers.option_code_raw_text.clear();
+        buffers.option_code.clear();
+        buffers.value_bldr.clear();
+        buffers.value_escaped_byte_idxs.clear();
+        buffers.value_delimiter_byte_idxs.clear();
+        buffers.option_value_delimiters.clear();
+
         ParseState {
             multi_char_option_code_requires_double_announcer,
-            line_or_env_arg: String::from(line_or_env_arg),
-            line_len: line_or_env_arg.chars().count(),
+            line_len: line_or_env_arg.len(),
+            line_or_env_arg,
             arg_parse_state: if first_arg_is_binary { ArgParseState::WaitBinary } else { ArgParseState::WaitOptionOrParam },
             option_parse_state: OptionParseState::InCode,
             env_line_approximate_char_idx: 0,
             env_arg_idx: 0,
+            option_env_arg_idx: 0,
+            value_start_env_arg_idx: 0,
             line_or_env_arg_char_idx: 0,
+            line_or_env_arg_byte_idx: 0,
             arg_start_char_idx: 0,
+            arg_start_byte_idx: 0,
             arg_start_env_line_approximate_char_idx: 0,
-            option_code_start_line_char_idx: 0,
+            option_code_start_byte_idx: 0,
+            option_code_end_byte_idx: 0,
+            option_code_span: Span::default(),
+            option_code_raw_text: buffers.option_code_raw_text,
+            value_start_char_idx: 0,
+            value_start_byte_idx: 0,
+            value_start_env_line_approximate_char_idx: 0,
             arg_quote_char: '\0',
             option_announcer_char: '\0',
-            option_code: String::from(""),
+            option_code: buffers.option_code,
             option_value_announcer_is_ambiguous: false,
             current_option_value_may_be_param: false,
             value_quoted: false,
-            value_bldr: String::with_capacity(30),
+            value_bldr: buffers.value_bldr,
+            value_escaped_byte_idxs: buffers.value_escaped_byte_idxs,
+            value_variables_expanded: false,
+            value_delimiter_byte_idxs: buffers.value_delimiter_byte_idxs,
+            value_at_element_start: false,
+            option_value_delimiters: buffers.option_value_delimiters,
+            pending_values_arg_idx: None,
+            pending_values_remaining: None,
             arg_count: 0,
             option_count: 0,
             param_count: 0,
             current_param_is_binary: false,
+            defer_possible_option_values: false,
+            possible_option_value_deferred: false,
         }
     }
 
-    pub(crate) fn set_option_code(& mut self, optional_ending_index: Option<usize>) -> Result<(), ParseError> {
-        let ending_index = optional_ending_index.unwrap_or(self.line_len);
-        let raw_option_code = &self.line_or_env_arg[self.option_code_start_line_char_idx..ending_index];
+    /// Release the line and the buffers so that they can be used by a later parse.
+    pub fn into_line_and_buffers(self) -> (Cow<'l, str>, ParseBuffers) {
+        let buffers = ParseBuffers {
+            option_code_raw_text: self.option_code_raw_text,
+            option_code: self.option_code,
+            value_bldr: self.value_bldr,
+            value_escaped_byte_idxs: self.value_escaped_byte_idxs,
+            value_delimiter_byte_idxs: self.value_delimiter_byte_idxs,
+            option_value_delimiters: self.option_value_delimiters,
+        };
+        (self.line_or_env_arg, buffers)
+    }
+
+    pub(crate) fn set_option_code(& mut self, optional_ending_byte_index: Option<usize>) -> Result<(), ParseError> {
+        let ending_byte_index = optional_ending_byte_index.unwrap_or(self.line_len);
+        self.option_code_span = self.span_from(self.arg_start_char_idx, self.arg_start_byte_idx);
+        self.option_code_end_byte_idx = ending_byte_index;
+        // reuse buffers
+        self.option_code_raw_text.clear();
+        self.option_code_raw_text.push_str(&self.line_or_env_arg[self.option_code_span.byte_index..self.option_code_span.end_byte_index]);
+        let raw_option_code = &self.line_or_env_arg[self.option_code_start_byte_idx..ending_byte_index];
 
-        let mut raw_option_iterator = raw_option_code.chars();
-        let optioned_first_char = raw_option_iterator.next();
-        match optioned_first_char {
-            None => {
-                self.option_code = String::from("");
+        self.option_code.clear();
+        match extract_option_code(raw_option_code, self.option_announcer_char, self.multi_char_option_code_requires_double_announcer) {
+            Ok(option_code) => {
+                self.option_code.push_str(option_code);
                 Ok(())
-            },
-            Some(first_char) => {
-                if !self.multi_char_option_code_requires_double_announcer {
-                    self.option_code = String::from(raw_option_code);
-                    Ok(())
-                } else {
-                    let first_char_is_announcer = first_char == self.option_announcer_char;
-                    let announcer_is_one_char_only = raw_option_iterator.next() != None;
-                    if announcer_is_one_char_only {
-                        if first_char_is_announcer {
-                            self.option_code = String::from("");
-                        } else {
-                            self.option_code = String::from(raw_option_code);
-                        }
-                        Ok(())
-                    } else {
-                        self.option_code = String::from(raw_option_code);
-                        if first_char_is_announcer {
-                            Ok(())
-                        } else {
-                            let error = self.create_option_error(ParseErrorTypeId::OptionCodeMissingDoubleAnnouncer);
-                            Err(error)
-                        }
-                    }
-                }
             }
+            Err(error_id) => {
+                self.option_code.push_str(raw_option_code);
+                Err(self.create_option_error(error_id))
+            }
+        }
+    }
+
+    /// Whether the option code currently being parsed was announced with a double announcer (eg. `--output`).
+    pub fn option_code_is_double_announced(&self) -> bool {
+        self.line_or_env_arg[self.option_code_start_byte_idx..].starts_with(self.option_announcer_char)
+    }
+
+    pub fn value_quote_char(&self) -> Option<char> {
+        if self.value_quoted {
+            Some(self.arg_quote_char)
+        } else {
+            None
+        }
+    }
+
+    /// Clear the value builder in preparation for a new parameter or option value.
+    pub fn clear_value(&mut self) {
+        self.value_bldr.clear();
+        self.value_escaped_byte_idxs.clear();
+        self.value_variables_expanded = false;
+        self.value_delimiter_byte_idxs.clear();
+        self.value_at_element_start = false;
+    }
+
+    /// Push a value delimiter character to the value builder and record its position.
+    pub fn push_value_delimiter(&mut self, delimiter: char) {
+        self.value_delimiter_byte_idxs.push((self.value_bldr.len(), delimiter));
+        self.value_bldr.push(delimiter);
+        self.value_at_element_start = true;
+    }
+
+    /// Split the value builder into the values delimited by the specified delimiter.  `value_text` is the value builder's
+    /// text as returned by [value_cow](Self::value_cow). If it is borrowed from the line, so are the values.
+    pub fn split_value(&self, delimiter: char, value_text: &Cow<'l, str>) -> Vec<Cow<'l, str>> {
+        let element_cow = |start_idx: usize, end_idx: usize| match value_text {
+            Cow::Borrowed(text) => Cow::Borrowed(&text[start_idx..end_idx]),
+            Cow::Owned(_) => Cow::Owned(String::from(&self.value_bldr[start_idx..end_idx])),
+        };
+        let mut values = Vec::new();
+        let mut start_idx = 0;
+        for (delimiter_idx, _) in self.value_delimiter_byte_idxs.iter().filter(|(_, recorded_delimiter)| *recorded_delimiter == delimiter) {
+            values.push(element_cow(start_idx, *delimiter_idx));
+            start_idx = delimiter_idx + delimiter.len_utf8();
         }
+        values.push(element_cow(start_idx, self.value_bldr.len()));
+        values
+    }
+
+    /// Push an escaped character to the value builder. The position of escaped characters is recorded so that they are not
+    /// treated as the start of a variable reference.
+    pub fn push_escaped_value_char(&mut self, unicode_char: char) {
+        self.value_escaped_byte_idxs.push(self.value_bldr.len());
+        self.value_bldr.push(unicode_char);
+    }
+
+    /// The character at the current position or `None` if at the end of the line or environment argument.
+    pub fn current_char(&self) -> Option<char> {
+        self.line_or_env_arg[self.line_or_env_arg_byte_idx..self.line_len].chars().next()
     }
 
     pub fn increment_env_line_approximate_char_idx(&mut self) {
         self.env_line_approximate_char_idx += 1;
     }
 
-    pub fn increment_env_arg_char_idx(&mut self) {
+    pub fn increment_env_arg_char_idx(&mut self, unicode_char: char) {
         self.line_or_env_arg_char_idx += 1;
+        self.line_or_env_arg_byte_idx += unicode_char.len_utf8();
+    }
+
+    /// Span from the specified start indices to the current position.
+    pub fn span_from(&self, start_char_idx: usize, start_byte_idx: usize) -> Span {
+        Span::new(start_char_idx, self.line_or_env_arg_char_idx, start_byte_idx, self.line_or_env_arg_byte_idx)
+    }
+
+    /// Span from the start of the current option value to the current position.
+    pub fn value_span(&self) -> Span {
+        self.span_from(self.value_start_char_idx, self.value_start_byte_idx)
+    }
+
+    /// Text of the current line or environment argument within a span.
+    pub fn raw_text(&self, span: &Span) -> &str {
+        &self.line_or_env_arg[span.byte_index..span.end_byte_index]
+    }
+
+    /// Text of the current line or environment argument within a span. Borrowed from the line if a line is being parsed.
+    pub fn raw_text_cow(&self, span: &Span) -> Cow<'l, str> {
+        match &self.line_or_env_arg {
+            Cow::Borrowed(line) => Cow::Borrowed(&line[span.byte_index..span.end_byte_index]),
+            Cow::Owned(env_arg) => Cow::Owned(String::from(&env_arg[span.byte_index..span.end_byte_index])),
+        }
+    }
+
+    /// Returns `text` borrowed from the line if a line is being parsed and the text appears verbatim in the line at
+    /// `start_byte_idx` (that is, it was not modified by escaping, embedded quotes or variable expansion). Otherwise
+    /// returns a copy of `text`.
+    pub fn text_cow(&self, text: &str, start_byte_idx: usize) -> Cow<'l, str> {
+        if let Cow::Borrowed(line) = &self.line_or_env_arg {
+            if let Some(line_text) = line.get(start_byte_idx..start_byte_idx + text.len()) {
+                if line_text == text {
+                    return Cow::Borrowed(line_text);
+                }
+            }
+        }
+        Cow::Owned(String::from(text))
+    }
+
+    /// The current option code, borrowed from the line if possible.
+    pub fn option_code_cow(&self) -> Cow<'l, str> {
+        let start_byte_idx = self.option_code_end_byte_idx.saturating_sub(self.option_code.len());
+        self.text_cow(&self.option_code, start_byte_idx)
+    }
+
+    /// The current parameter or option value, borrowed from the line if possible. `start_byte_idx` is the start of the
+    /// argument or value in the line.
+    pub fn value_cow(&self, start_byte_idx: usize) -> Cow<'l, str> {
+        let content_start_byte_idx = if self.value_quoted {
+            start_byte_idx + self.arg_quote_char.len_utf8()
+        } else {
+            start_byte_idx
+        };
+        self.text_cow(&self.value_bldr, content_start_byte_idx)
+    }
+
+    /// Span from the start of the current option to the current position. If the option's value is in a different environment
+    /// argument to its code, the span starts at the value.
+    pub fn option_span(&self) -> Span {
+        if self.option_parse_state != OptionParseState::InCode && self.value_start_env_arg_idx != self.option_env_arg_idx {
+            self.value_span()
+        } else {
+            self.span_from(self.arg_start_char_idx, self.arg_start_byte_idx)
+        }
     }
 
     pub fn create_option_error(&self, error_id: ParseErrorTypeId) -> ParseError {
-        ParseError::new_option(error_id, self.env_line_approximate_char_idx,
+        let (span, raw_text) = match self.option_parse_state {
+            OptionParseState::WaitOptionValue => (self.option_code_span, self.option_code_raw_text.as_str()),
+            _ => {
+                let span = self.option_span();
+                (span, self.raw_text(&span))
+            }
+        };
+        ParseError::new_option(error_id, self.env_line_approximate_char_idx, span, raw_text,
             self.arg_count, self.option_count, &self.option_code, &self.value_bldr)
     }
 
     pub fn create_param_error(&self, error_id: ParseErrorTypeId) -> ParseError {
-        ParseError::new_param(error_id, self.env_line_approximate_char_idx, self.arg_count, self.option_count, &self.value_bldr)
+        let span = self.span_from(self.arg_start_char_idx, self.arg_start_byte_idx);
+        ParseError::new_param(error_id, self.env_line_approximate_char_idx, span, self.raw_text(&span), self.arg_count, self.param_count,
+            &self.value_bldr)
+    }
+}
+
+/// Extracts the option code from the text which follows an option's first announcer character.
+pub(crate) fn extract_option_code(raw_option_code: &str, option_announcer_char: char, multi_char_option_code_requires_double_announcer: bool)
+    -> Result<&str, ParseErrorTypeId>
+{
+    let mut raw_option_iterator = raw_option_code.chars();
+    match raw_option_iterator.next() {
+        None => Ok(raw_option_code),
+        Some(first_char) => {
+            if !multi_char_option_code_requires_double_announcer {
+                Ok(raw_option_code)
+            } else {
+                if first_char == option_announcer_char {
+                    Ok(&raw_option_code[first_char.len_utf8()..])
+                } else {
+                    let code_is_one_char_only = raw_option_iterator.next().is_none();
+                    if code_is_one_char_only {
+                        Ok(raw_option_code)
+                    } else {
+                        Err(ParseErrorTypeId::OptionCodeMissingDoubleAnnouncer)
+                    }
+                }
+            }
+        }
     }
 }
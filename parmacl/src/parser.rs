@@ -1,11 +1,27 @@
+use std::borrow::Cow;
 use std::env;
+use std::mem;
+use std::io::BufRead;
+use std::ops::ControlFlow;
 use crate::env_char::EnvChar;
 use crate::parse_error_type_id::{ParseErrorTypeId};
 use crate::parse_error::{ParseError};
 use crate::regex_or_text::{RegexOrText};
-use crate::arg::{Arg, Args, OptionProperties, ParamProperties, BinaryProperties};
-use crate::matcher::{Matcher, Matchers, OptionHasValue, DefaultTagType, MatchArgTypeId};
-use crate::parse_state::{ParseState, ArgParseState, OptionParseState};
+use crate::arg::{Arg, Args, OptionProperties, ParamProperties, BinaryProperties, ValueSource};
+use crate::matcher::{Matcher, Matchers, OptionHasValue, OptionValueArity, MapDuplicates, DefaultTagType, MatchArgTypeId};
+use crate::parse_state::{ParseState, ParseBuffers, ArgParseState, OptionParseState};
+use crate::parse_session::{ParseSession};
+use crate::command_reader::{CommandReader};
+use crate::arg_visitor::{ArgVisitor};
+use crate::arg_iter::{ArgIter};
+use crate::validation::{ValidationIssue, validate_parser};
+use crate::tokenizer::{Tokenizer};
+use crate::termination::{Termination};
+use crate::span::{Span};
+use crate::command::{Command, split_commands};
+use crate::config_file::{ConfigFile};
+use crate::matcher_index::{MatcherIndex};
+use crate::variable::{VariableSyntax, VariableResolver, EnvVariableResolver, expand_variables};
 
 /// Default [quote characters](Parser::quote_chars) for line parsing.
 pub const DEFAULT_LINE_QUOTE_CHARS: [char; 1] = ['"'];
@@ -35,6 +51,14 @@ pub const DEFAULT_LINE_ESCAPEABLE_CHARS: [char; 0] = [];
 pub const DEFAULT_LINE_PARSE_TERMINATE_CHARS: [char; 0] = [];
 /// Default [first argument is binary](Parser::first_arg_is_binary) for line parsing.
 pub const DEFAULT_LINE_FIRST_ARG_IS_BINARY: bool = true;
+/// Default [command separators](Parser::command_separators) for line parsing.
+pub const DEFAULT_LINE_COMMAND_SEPARATORS: [&str; 4] = [";", "&&", "||", "|"];
+/// Default [variable syntaxes](Parser::variable_syntaxes) for line parsing.
+pub const DEFAULT_LINE_VARIABLE_SYNTAXES: [VariableSyntax; 0] = [];
+/// Default [variable literal quote characters](Parser::variable_literal_quote_chars) for line parsing.
+pub const DEFAULT_LINE_VARIABLE_LITERAL_QUOTE_CHARS: [char; 0] = [];
+/// Default [option code abbreviations](Parser::option_code_abbreviations) for line parsing.
+pub const DEFAULT_LINE_OPTION_CODE_ABBREVIATIONS: bool = false;
 
 /// Default [quote characters](Parser::quote_chars) for environment arguments parsing.
 pub const DEFAULT_ENV_ARGS_QUOTE_CHARS: [char; 0] = [];
@@ -65,6 +89,14 @@ pub const DEFAULT_ENV_ARGS_ESCAPEABLE_CHARS: [char; 0] = [];
 pub const DEFAULT_ENV_ARGS_PARSE_TERMINATE_CHARS: [char; 0] = [];
 /// Default [first argument is binary](Parser::first_arg_is_binary) for environment arguments parsing.
 pub const DEFAULT_ENV_ARGS_FIRST_ARG_IS_BINARY: bool = true;
+/// Default [command separators](Parser::command_separators) for environment arguments parsing.
+pub const DEFAULT_ENV_ARGS_COMMAND_SEPARATORS: [&str; 0] = [];
+/// Default [variable syntaxes](Parser::variable_syntaxes) for environment arguments parsing.
+pub const DEFAULT_ENV_ARGS_VARIABLE_SYNTAXES: [VariableSyntax; 0] = [];
+/// Default [variable literal quote characters](Parser::variable_literal_quote_chars) for environment arguments parsing.
+pub const DEFAULT_ENV_ARGS_VARIABLE_LITERAL_QUOTE_CHARS: [char; 0] = [];
+/// Default [option code abbreviations](Parser::option_code_abbreviations) for environment arguments parsing.
+pub const DEFAULT_ENV_ARGS_OPTION_CODE_ABBREVIATIONS: bool = false;
 
 /// A Parser is used to parse a command line (or environmental arguments).  It has:
 /// * properties which define the style of the command line to be parsed,
@@ -77,13 +109,14 @@ pub const DEFAULT_ENV_ARGS_FIRST_ARG_IS_BINARY: bool = true;
 /// The style of a command line can configured with the following:
 /// * Parameters and option values can be quoted ([quote_chars](Self::quote_chars))
 /// * Allow quote characters to be embedded in quoted parameters and option values using double quotes
-/// ([embed_quote_char_with_double](Self::embed_quote_char_with_double))
+///   ([embed_quote_char_with_double](Self::embed_quote_char_with_double))
 /// * Whether parameters are case sensitive ([params_case_sensitive](Self::params_case_sensitive))
 /// * The characters which announce an option ([option_announcer_chars](Self::option_announcer_chars))
 /// * Whether option codes with more than one character require 2 announcer characters
-/// ([multi_char_option_code_requires_double_announcer](Self::multi_char_option_code_requires_double_announcer))
+///   ([multi_char_option_code_requires_double_announcer](Self::multi_char_option_code_requires_double_announcer))
 /// * Whether option codes are case sensitive ([option_codes_case_sensitive](Self::option_codes_case_sensitive))
 /// * Whether option codes can be empty strings ([option_code_can_be_empty](Self::option_code_can_be_empty))
+/// * Whether option codes can be abbreviated to a unique prefix ([option_code_abbreviations](Self::option_code_abbreviations))
 /// * The characters which announce an option value ([option_value_announcer_chars](Self::option_value_announcer_chars))
 /// * Whether option values are case sensitive ([option_values_case_sensitive](Self::option_values_case_sensitive))
 /// * Optionally define a character which will escape characters with special purpose ([escape_char](Self::escape_char))
@@ -91,6 +124,9 @@ pub const DEFAULT_ENV_ARGS_FIRST_ARG_IS_BINARY: bool = true;
 /// * Specify which literal characters can be escaped ([escapeable_chars](Self::escapeable_chars))
 /// * Whether the first argument is the binary name ([first_arg_is_binary](Self::first_arg_is_binary))
 /// * The characters which will terminate the parsing of the line early ([parse_terminate_chars](Self::parse_terminate_chars))
+/// * The strings which separate commands in a multi-command line ([command_separators](Self::command_separators))
+/// * Whether variable references in parameters and option values are expanded ([variable_syntaxes](Self::variable_syntaxes),
+///   [variable_literal_quote_chars](Self::variable_literal_quote_chars) and [variable_resolver](Self::variable_resolver))
 ///
 /// The [new](Self::new) constructor will create a Parser with base defaults that are a good starting point for parsing a command line.
 /// The [with_env_args_defaults](Self::with_env_args_defaults) constructor has base defaults for parsing environmental arguments.
@@ -109,11 +145,24 @@ pub const DEFAULT_ENV_ARGS_FIRST_ARG_IS_BINARY: bool = true;
 /// 
 /// If parsing was successful, these 3 functions will return a vector of [parsed arguments](Args). Otherwise they will return an
 /// [ParseError](ParseError) struct detailing the type of parse error and its location in the line.
+/// 
+/// Each parse function has a `_with_termination` variant ([parse_line_with_termination](Self::parse_line_with_termination),
+/// [parse_env_args_with_termination](Self::parse_env_args_with_termination), [parse_env_with_termination](Self::parse_env_with_termination))
+/// which also returns a [Termination](Termination) if parsing was terminated by a [parse terminate character](Self::parse_terminate_chars).
+/// This holds the terminate character and the unparsed remainder of the line.
+/// 
+/// The [parse_commands](Self::parse_commands) function parses a line containing multiple commands separated by
+/// [command separators](Self::command_separators).
+/// 
+/// The [tokenize](Self::tokenize) function splits a command line into lexical tokens without matching arguments.
+/// 
+/// If a Parser has many matchers, [compile](Self::compile) it after configuring the matchers to speed up matching.
 pub struct Parser<O: Default = DefaultTagType, P: Default = DefaultTagType> {
     quote_chars: Vec<char>,
     option_announcer_chars: Vec<char>,
     option_codes_case_sensitive: bool,
     option_code_can_be_empty: bool,
+    option_code_abbreviations: bool,
     multi_char_option_code_requires_double_announcer: bool,
     option_value_announcer_chars: Vec<char>,
     option_values_case_sensitive: bool,
@@ -124,9 +173,16 @@ pub struct Parser<O: Default = DefaultTagType, P: Default = DefaultTagType> {
     escapeable_chars: Vec<char>,
     first_arg_is_binary: bool,
     parse_terminate_chars: Vec<char>,
+    command_separators: Vec<String>,
+    variable_syntaxes: Vec<VariableSyntax>,
+    variable_literal_quote_chars: Vec<char>,
+    variable_resolver: Box<dyn VariableResolver>,
+    env_var_resolver: Box<dyn VariableResolver>,
+    config_files: Vec<ConfigFile>,
 
     matchers: Matchers<O, P>,
     any_matcher: Matcher<O, P>,
+    matcher_index: Option<MatcherIndex>,
 }
 
 impl<O: Default, P: Default> Parser<O, P> {
@@ -138,6 +194,7 @@ impl<O: Default, P: Default> Parser<O, P> {
             option_announcer_chars: DEFAULT_LINE_OPTION_ANNOUNCER_CHARS.to_vec(),
             option_codes_case_sensitive: DEFAULT_LINE_OPTION_CODES_CASE_SENSITIVE,
             option_code_can_be_empty: DEFAULT_LINE_OPTION_CODE_CAN_BE_EMPTY,
+            option_code_abbreviations: DEFAULT_LINE_OPTION_CODE_ABBREVIATIONS,
             multi_char_option_code_requires_double_announcer: DEFAULT_LINE_MULTI_CHAR_OPTION_CODE_REQUIRES_DOUBLE_ANNOUNCER,
             option_value_announcer_chars: DEFAULT_LINE_OPTION_VALUE_ANNOUNCER_CHARS.to_vec(),
             option_values_case_sensitive: DEFAULT_LINE_OPTION_VALUES_CASE_SENSITIVE,
@@ -148,9 +205,16 @@ impl<O: Default, P: Default> Parser<O, P> {
             escapeable_chars: DEFAULT_LINE_ESCAPEABLE_CHARS.to_vec(),
             parse_terminate_chars: DEFAULT_LINE_PARSE_TERMINATE_CHARS.to_vec(),
             first_arg_is_binary: DEFAULT_LINE_FIRST_ARG_IS_BINARY,
+            command_separators: DEFAULT_LINE_COMMAND_SEPARATORS.iter().map(|separator| String::from(*separator)).collect(),
+            variable_syntaxes: DEFAULT_LINE_VARIABLE_SYNTAXES.to_vec(),
+            variable_literal_quote_chars: DEFAULT_LINE_VARIABLE_LITERAL_QUOTE_CHARS.to_vec(),
+            variable_resolver: Box::new(EnvVariableResolver),
+            env_var_resolver: Box::new(EnvVariableResolver),
+            config_files: Vec::new(),
 
             matchers: Matchers::new(),
             any_matcher: Matcher::new(""),
+            matcher_index: None,
         }
     }
 
@@ -234,6 +298,7 @@ impl<O: Default, P: Default> Parser<O, P> {
     /// Sets [option_codes_case_sensitive](Self::option_codes_case_sensitive)
     pub fn set_option_codes_case_sensitive(&mut self, value: bool) -> &mut Self {
         self.option_codes_case_sensitive = value;
+        self.matcher_index = None;
         self
     }
 
@@ -253,6 +318,27 @@ impl<O: Default, P: Default> Parser<O, P> {
         self
     }
 
+    /// Specifies whether an option code can be abbreviated to a prefix of a matcher's option code (eg. `--verb` for `--verbose`).
+    /// 
+    /// Only applies to text option codes (not regex) and to option codes which do not exactly match any matcher.  If
+    /// [multi_char_option_code_requires_double_announcer](Self::multi_char_option_code_requires_double_announcer) is true, only
+    /// option codes announced with a double announcer are expanded (eg. `-o` is not expanded to `--output`), otherwise only
+    /// option codes with more than one character are expanded.  If the prefix is the beginning of option codes in more than one
+    /// matcher, an [AmbiguousOption](ParseErrorTypeId::AmbiguousOption) error is returned with the matching option codes in
+    /// [candidates](crate::ParseErrorContext::candidates).  If the prefix is unique,
+    /// [OptionProperties.code](crate::OptionProperties::code) holds the full option code.
+    /// 
+    /// Default: false
+    pub fn option_code_abbreviations(&self) -> bool {
+        self.option_code_abbreviations
+    }
+
+    /// Sets [option_code_abbreviations](Self::option_code_abbreviations)
+    pub fn set_option_code_abbreviations(&mut self, value: bool) -> &mut Self {
+        self.option_code_abbreviations = value;
+        self
+    }
+
     /// Specifies whether option codes with more than one character must be announced with 2 successive option announcer characters.
     /// 
     /// This convention is common but not necessary.
@@ -317,6 +403,7 @@ impl<O: Default, P: Default> Parser<O, P> {
     /// Sets [params_case_sensitive](Self::params_case_sensitive)
     pub fn set_params_case_sensitive(&mut self, value: bool) -> &mut Self {
         self.params_case_sensitive = value;
+        self.matcher_index = None;
         self
     }
 
@@ -431,7 +518,8 @@ impl<O: Default, P: Default> Parser<O, P> {
     /// 
     /// If any of the characters in this array are encountered outside a quoted value, then that character
     /// and all remaining characters in the command line are ignored.  This can be used to ignore standard input/output
-    /// redirection and the end of a command line.
+    /// redirection and the end of a command line.  Use one of the `_with_termination` parse functions (eg.
+    /// [parse_line_with_termination](Self::parse_line_with_termination)) to get the terminate character and the ignored remainder.
     ///
     /// Default: `[]`  (Empty array)
     pub fn parse_terminate_chars(&self) -> &[char] {
@@ -444,6 +532,125 @@ impl<O: Default, P: Default> Parser<O, P> {
         self
     }
 
+    /// Get the array of strings which separate commands in a line parsed with [parse_commands](Self::parse_commands).
+    ///
+    /// A separator is only recognised outside of quoted values and when not escaped. It does not need to be surrounded by
+    /// whitespace. Where one separator starts with another (eg. `|` and `||`), the longest separator is used.  Separators
+    /// are ignored by the other parse functions.
+    ///
+    /// Line Default: `[";", "&&", "||", "|"]`\
+    /// Env args Default: `[]` (Empty array)
+    pub fn command_separators(&self) -> &[String] {
+        &self.command_separators
+    }
+
+    /// Set [command_separators](Self::command_separators)
+    pub fn set_command_separators(&mut self, value: &[&str]) -> &mut Self {
+        self.command_separators = value.iter().map(|separator| String::from(*separator)).collect();
+        self
+    }
+
+    /// Get the array of syntaxes with which variables can be referenced in parameters and option values.
+    ///
+    /// If this array is not empty, variable references (eg. `$HOME` or `%TEMP%`) in parameters and option values are replaced
+    /// with the variable's value before the argument is matched.  Values are supplied by the [variable_resolver](Self::variable_resolver).
+    /// If a variable cannot be resolved (and no default is specified), its reference is left unchanged.
+    ///
+    /// Variables are not expanded in the binary argument, in option codes, in escaped characters or in values quoted with a
+    /// [variable literal quote character](Self::variable_literal_quote_chars).
+    ///
+    /// Default: `[]` (Empty array - variables are not expanded)
+    pub fn variable_syntaxes(&self) -> &[VariableSyntax] {
+        &self.variable_syntaxes
+    }
+
+    /// Set [variable_syntaxes](Self::variable_syntaxes)
+    pub fn set_variable_syntaxes(&mut self, value: &[VariableSyntax]) -> &mut Self {
+        self.variable_syntaxes = Vec::from(value);
+        self
+    }
+
+    /// Get the array of quote characters which quote values literally.  Variables are not expanded in parameters and option values
+    /// enclosed with one of these characters.  Only has effect for characters which are also in [quote_chars](Self::quote_chars).
+    ///
+    /// Default: `[]` (Empty array)
+    pub fn variable_literal_quote_chars(&self) -> &[char] {
+        &self.variable_literal_quote_chars
+    }
+
+    /// Set [variable_literal_quote_chars](Self::variable_literal_quote_chars)
+    pub fn set_variable_literal_quote_chars(&mut self, value: &[char]) -> &mut Self {
+        self.variable_literal_quote_chars = Vec::from(value);
+        self
+    }
+
+    /// Get the [VariableResolver] which supplies the values of variables referenced in parameters and option values.
+    /// See [variable_syntaxes](Self::variable_syntaxes).
+    ///
+    /// Default: [EnvVariableResolver] (resolves from environment variables)
+    pub fn variable_resolver(&self) -> &dyn VariableResolver {
+        self.variable_resolver.as_ref()
+    }
+
+    /// Set [variable_resolver](Self::variable_resolver)
+    pub fn set_variable_resolver<R: VariableResolver + 'static>(&mut self, value: R) -> &mut Self {
+        self.variable_resolver = Box::new(value);
+        self
+    }
+
+    /// Get the [VariableResolver] which supplies the values of matchers' [environment variables](Matcher::env_var_name).  This
+    /// is independent of [variable_resolver](Self::variable_resolver) which is only used to expand variables referenced in the
+    /// command line.
+    ///
+    /// Default: [EnvVariableResolver] (resolves from environment variables)
+    pub fn env_var_resolver(&self) -> &dyn VariableResolver {
+        self.env_var_resolver.as_ref()
+    }
+
+    /// Set [env_var_resolver](Self::env_var_resolver)
+    pub fn set_env_var_resolver<R: VariableResolver + 'static>(&mut self, value: R) -> &mut Self {
+        self.env_var_resolver = Box::new(value);
+        self
+    }
+
+    /// Get the array of [configuration files](ConfigFile) which supply values for arguments not in the command line.
+    ///
+    /// After a command line is parsed, an argument is added for each matcher which did not match any argument but for which
+    /// a fallback value exists.  Configuration file keys are mapped to [matcher names](Matcher::name).  Fallback values have
+    /// the following precedence (highest first):
+    /// 1. The matcher's [environment variable](Matcher::env_var_name)
+    /// 1. Configuration files. Files later in this array override files earlier in the array. Within a file, a later entry
+    ///    overrides an earlier entry with the same key.
+    /// 1. The matcher's [default value](Matcher::default_value)
+    ///
+    /// Arguments in the command line always take precedence over fallback values.  The `value_source` property of an argument
+    /// whose value came from a configuration file holds the [location](crate::ConfigLocation) (file and line) of the value.
+    ///
+    /// A fallback value must match the matcher's [value_text](Matcher::value_text). If the matcher's options never have a value,
+    /// the fallback value must instead be `true`, `yes`, `on` or `1` (the option is enabled) or `false`, `no`, `off` or `0`
+    /// (the option is [disabled](crate::OptionProperties::enabled)), ignoring case. Otherwise an
+    /// [InvalidFallbackValue](crate::ParseErrorTypeId::InvalidFallbackValue) error is returned.
+    /// If a key in a configuration file is not the name of any matcher, an
+    /// [UnknownConfigKey](crate::ParseErrorTypeId::UnknownConfigKey) error is returned.
+    ///
+    /// Default: `[]` (Empty array)
+    pub fn config_files(&self) -> &[ConfigFile] {
+        &self.config_files
+    }
+
+    /// Set [config_files](Self::config_files)
+    pub fn set_config_files(&mut self, value: Vec<ConfigFile>) -> &mut Self {
+        self.config_files = value;
+        self
+    }
+
+    /// Add a configuration file to the end of [config_files](Self::config_files). It will override configuration files already
+    /// added.
+    pub fn push_config_file(&mut self, value: ConfigFile) -> &mut Self {
+        self.config_files.push(value);
+        self
+    }
+
 }
 
 impl<O: Default, P: Default> Parser<O, P> {
@@ -455,6 +662,7 @@ impl<O: Default, P: Default> Parser<O, P> {
             .set_option_announcer_chars(&DEFAULT_LINE_OPTION_ANNOUNCER_CHARS)
             .set_option_codes_case_sensitive(DEFAULT_LINE_OPTION_CODES_CASE_SENSITIVE)
             .set_option_code_can_be_empty(DEFAULT_LINE_OPTION_CODE_CAN_BE_EMPTY)
+            .set_option_code_abbreviations(DEFAULT_LINE_OPTION_CODE_ABBREVIATIONS)
             .set_multi_char_option_code_requires_double_announcer(DEFAULT_LINE_MULTI_CHAR_OPTION_CODE_REQUIRES_DOUBLE_ANNOUNCER)
             .set_option_value_announcer_chars(&DEFAULT_LINE_OPTION_VALUE_ANNOUNCER_CHARS)
             .set_option_values_case_sensitive(DEFAULT_LINE_OPTION_VALUES_CASE_SENSITIVE)
@@ -464,7 +672,10 @@ impl<O: Default, P: Default> Parser<O, P> {
             .set_escapeable_logical_chars(&DEFAULT_LINE_ESCAPEABLE_LOGICAL_CHARS)
             .set_escapeable_chars(&DEFAULT_LINE_ESCAPEABLE_CHARS)
             .set_parse_terminate_chars(&DEFAULT_LINE_PARSE_TERMINATE_CHARS)
-            .set_first_arg_is_binary(DEFAULT_LINE_FIRST_ARG_IS_BINARY);
+            .set_first_arg_is_binary(DEFAULT_LINE_FIRST_ARG_IS_BINARY)
+            .set_command_separators(&DEFAULT_LINE_COMMAND_SEPARATORS)
+            .set_variable_syntaxes(&DEFAULT_LINE_VARIABLE_SYNTAXES)
+            .set_variable_literal_quote_chars(&DEFAULT_LINE_VARIABLE_LITERAL_QUOTE_CHARS);
     }
 
     /// Set Parser properties to their default values for parsing environmental arguments.
@@ -474,6 +685,7 @@ impl<O: Default, P: Default> Parser<O, P> {
             .set_option_announcer_chars(&DEFAULT_ENV_ARGS_OPTION_ANNOUNCER_CHARS)
             .set_option_codes_case_sensitive(DEFAULT_ENV_ARGS_OPTION_CODES_CASE_SENSITIVE)
             .set_option_code_can_be_empty(DEFAULT_ENV_ARGS_OPTION_CODE_CAN_BE_EMPTY)
+            .set_option_code_abbreviations(DEFAULT_ENV_ARGS_OPTION_CODE_ABBREVIATIONS)
             .set_multi_char_option_code_requires_double_announcer(DEFAULT_ENV_ARGS_MULTI_CHAR_OPTION_CODE_REQUIRES_DOUBLE_ANNOUNCER)
             .set_option_value_announcer_chars(&DEFAULT_ENV_ARGS_OPTION_VALUE_ANNOUNCER_CHARS)
             .set_option_values_case_sensitive(DEFAULT_ENV_ARGS_OPTION_VALUES_CASE_SENSITIVE)
@@ -483,7 +695,10 @@ impl<O: Default, P: Default> Parser<O, P> {
             .set_escapeable_logical_chars(&DEFAULT_ENV_ARGS_ESCAPEABLE_LOGICAL_CHARS)
             .set_escapeable_chars(&DEFAULT_ENV_ARGS_ESCAPEABLE_CHARS)
             .set_parse_terminate_chars(&DEFAULT_ENV_ARGS_PARSE_TERMINATE_CHARS)
-            .set_first_arg_is_binary(DEFAULT_ENV_ARGS_FIRST_ARG_IS_BINARY);
+            .set_first_arg_is_binary(DEFAULT_ENV_ARGS_FIRST_ARG_IS_BINARY)
+            .set_command_separators(&DEFAULT_ENV_ARGS_COMMAND_SEPARATORS)
+            .set_variable_syntaxes(&DEFAULT_ENV_ARGS_VARIABLE_SYNTAXES)
+            .set_variable_literal_quote_chars(&DEFAULT_ENV_ARGS_VARIABLE_LITERAL_QUOTE_CHARS);
     }
 
     /// The array of [matchers](Matcher) registered with the Parser.
@@ -516,6 +731,7 @@ impl<O: Default, P: Default> Parser<O, P> {
         let index = self.matchers.len();
         matcher.set_index(index);
         self.matchers.push(matcher);
+        self.matcher_index = None;
         &mut self.matchers[index]
     }
 
@@ -535,11 +751,29 @@ impl<O: Default, P: Default> Parser<O, P> {
     /// Delete the [matcher](Matcher) at the position in the list specified by the `index` parameter.
     pub fn delete_matcher_at(&mut self, index: usize) {
         self.matchers.remove(index);
+        self.matcher_index = None;
     }
 
     /// Delete all [matcher](Matcher)s from the Parser's list of matchers.
     pub fn clear_matchers(&mut self) {
         self.matchers.clear();
+        self.matcher_index = None;
+    }
+
+    /// Build an index of the matchers' text option codes and parameter value texts so that parsing only tries the matchers which
+    /// can match an argument instead of trying every matcher in turn.  This speeds up parsing when there are many matchers.
+    /// 
+    /// Compile after all matchers have been configured. The index is discarded if matchers are added or deleted, or if
+    /// [option_codes_case_sensitive](Self::option_codes_case_sensitive) or [params_case_sensitive](Self::params_case_sensitive)
+    /// are changed, in which case parsing tries every matcher until the Parser is compiled again.  Compiling does not change
+    /// the result of parsing: if more than one matcher can match an argument, the first of these in the list is still used.
+    pub fn compile(&mut self) {
+        self.matcher_index = Some(MatcherIndex::new(&self.matchers, self.option_codes_case_sensitive, self.params_case_sensitive));
+    }
+
+    /// Returns true if the Parser has been [compiled](Self::compile) and its index is still current.
+    pub fn is_compiled(&self) -> bool {
+        self.matcher_index.is_some()
     }
 
     /// Find and return the first [matcher](Matcher) in the list whose name equals the value of the `name` parameter.
@@ -549,28 +783,56 @@ impl<O: Default, P: Default> Parser<O, P> {
         self.matchers.iter().find(|&matcher| matcher.name() == name)
     }
 
+    /// Check that the matchers are coherent. For example, that no two matchers have the same name and that no option matcher
+    /// is unreachable because an earlier matcher has the same option code.
+    ///
+    /// Returns the [issues](ValidationIssue) found (empty if none).  Each issue has a [severity](crate::ValidationSeverity).
+    /// This can be used in a unit test to check an application's parser configuration.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        validate_parser(self)
+    }
+
     /// Parse this applications environmental arguments.
     /// 
     /// If successful, returns a success result holding an array of the parsed arguments. Otherwise return an error result
     /// containing a [ParseError](ParseError) struct which holds the error details.
-    pub fn parse_env(&self) -> Result<Args<O, P>, ParseError> {
+    pub fn parse_env(&self) -> Result<Args<'_, O, P>, ParseError> {
         self.parse_env_args(env::args())
     }
 
+    /// Parse this applications environmental arguments and also return where parsing was terminated.
+    /// 
+    /// Same as [parse_env](Self::parse_env) however the success result also holds a [Termination](Termination) if
+    /// parsing was terminated by a [parse terminate character](Self::parse_terminate_chars).
+    pub fn parse_env_with_termination(&self) -> Result<(Args<'_, O, P>, Option<Termination>), ParseError> {
+        self.parse_env_args_with_termination(env::args())
+    }
+
     /// Parse the environmental arguments passed in the `env_args` parameter.
     /// 
     /// If successful, returns a success result holding an array of the [parsed arguments](Args). Otherwise return an error result
     /// containing a [ParseError](ParseError) struct which holds the error details.
-    pub fn parse_env_args(&self, env_args: env::Args) -> Result<Args<O, P>, ParseError> {
+    pub fn parse_env_args(&self, env_args: env::Args) -> Result<Args<'_, O, P>, ParseError> {
+        let (args, _) = self.parse_env_args_with_termination(env_args)?;
+        Ok(args)
+    }
+
+    /// Parse the environmental arguments passed in the `env_args` parameter and also return where parsing was terminated.
+    /// 
+    /// Same as [parse_env_args](Self::parse_env_args) however the success result also holds a [Termination](Termination) if
+    /// parsing was terminated by a [parse terminate character](Self::parse_terminate_chars).
+    pub fn parse_env_args_with_termination(&self, env_args: env::Args) -> Result<(Args<'_, O, P>, Option<Termination>), ParseError> {
         let mut args = Vec::new();
         let mut parse_state = ParseState::new(
-            "",
+            Cow::Borrowed(""),
             self.first_arg_is_binary,
             self.multi_char_option_code_requires_double_announcer,
         );
 
-        let mut more = true;
-        for (env_arg_idx, env_arg) in env_args.enumerate() {
+        let mut termination: Option<Termination> = None;
+        let mut env_args_iter = env_args.enumerate();
+        for (env_arg_idx, env_arg) in env_args_iter.by_ref() {
+            let mut more = true;
             if env_arg_idx > 0 {
                 more = self.process_char(&mut parse_state, EnvChar::Separator, &mut args)?;
 
@@ -581,18 +843,21 @@ impl<O: Default, P: Default> Parser<O, P> {
 
             if more {
                 parse_state.env_arg_idx = env_arg_idx;
-                parse_state.line_or_env_arg = env_arg.clone();
+                parse_state.line_or_env_arg = Cow::Owned(env_arg.clone());
+                parse_state.line_len = env_arg.len();
                 parse_state.line_or_env_arg_char_idx = 0;
+                parse_state.line_or_env_arg_byte_idx = 0;
     
                 for unicode_char in env_arg.chars() {
                     let env_char = EnvChar::Unicode(unicode_char);
                     more = self.process_char(&mut parse_state, env_char, &mut args)?;
 
                     if more {
-                        parse_state.increment_env_arg_char_idx();
+                        parse_state.increment_env_arg_char_idx(unicode_char);
                         parse_state.increment_env_line_approximate_char_idx();
                     } else {
                         // ignore rest of line
+                        termination = Some(self.create_termination(&parse_state, unicode_char));
                         break;
                     }
                 }
@@ -604,53 +869,301 @@ impl<O: Default, P: Default> Parser<O, P> {
             }
         }
 
+        if let Some(termination) = termination.as_mut() {
+            termination.remaining_env_args = env_args_iter.map(|(_, env_arg)| env_arg).collect();
+        }
+
         self.finalise_parse(&mut parse_state, &mut args)?;
+        self.finalise_pending_option_values(&mut parse_state, &args)?;
+        self.aggregate_counted_options(&mut parse_state, &mut args);
+        self.add_fallback_args(&mut parse_state, &mut args)?;
+        self.resolve_option_choices(&mut args)?;
+        self.resolve_map_options(&mut args)?;
 
-        Ok(args)
+        Ok((args, termination))
     }
 
     /// Parse a command line.
     /// 
     /// If successful, returns a success result holding an array of the [parsed arguments](Args). Otherwise return an error result
     /// containing a [ParseError](ParseError) struct which holds the error details.
-    pub fn parse_line(&self, line: &str) -> Result<Args<O, P>, ParseError> {
+    ///
+    /// Text in the parsed arguments borrows from `line` where it did not need to be modified (eg. by unescaping or
+    /// removing embedded quotes), so the arguments cannot outlive the line.
+    pub fn parse_line<'a>(&'a self, line: &'a str) -> Result<Args<'a, O, P>, ParseError> {
+        let (args, _) = self.parse_line_with_termination(line)?;
+        Ok(args)
+    }
+
+    /// Parse a command line and also return where parsing was terminated.
+    /// 
+    /// Same as [parse_line](Self::parse_line) however the success result also holds a [Termination](Termination) if
+    /// parsing was terminated by a [parse terminate character](Self::parse_terminate_chars).  The termination holds the
+    /// terminate character and the remainder of the line which was not parsed.
+    pub fn parse_line_with_termination<'a>(&'a self, line: &'a str) -> Result<(Args<'a, O, P>, Option<Termination>), ParseError> {
+        self.parse_line_range(line, 0, 0, line.len())
+    }
+
+    /// Parse a command line and pass each argument to `visitor` as it is completed, instead of returning an array of arguments.
+    ///
+    /// The visitor can stop parsing early and can decide whether a word following an option code is the option's value or a
+    /// parameter. See [ArgVisitor] for details.
+    ///
+    /// If successful, returns a success result holding a [Termination](Termination) if parsing was terminated by a
+    /// [parse terminate character](Self::parse_terminate_chars).  If the visitor stopped parsing, the result holds `None`.
+    /// Otherwise returns an error result containing a [ParseError](ParseError) which has also been passed to the visitor.
+    pub fn parse_line_with<'a, V: ArgVisitor<'a, O, P>>(&'a self, line: &'a str, visitor: &mut V)
+        -> Result<Option<Termination>, ParseError>
+    {
+        let result = self.visit_line(line, visitor);
+        if let Err(error) = &result {
+            visitor.on_error(error);
+        }
+        result
+    }
+
+    /// Parse a command line on demand.
+    ///
+    /// Returns an [ArgIter] iterator which parses the line as far as needed to return each argument. This allows a caller to
+    /// stop parsing (eg. after finding a subcommand) without the rest of the line being parsed. See [ArgIter] for details.
+    pub fn iter_line<'a>(&'a self, line: &'a str) -> ArgIter<'a, O, P> {
+        ArgIter::new(self, line)
+    }
+
+    /// Parse a line containing multiple commands separated by [command separators](Self::command_separators) (eg.
+    /// `load a; run -v && save b`).
+    /// 
+    /// The line is split into commands and each command is parsed with the matchers as if it were a separate line. That is,
+    /// if [first_arg_is_binary](Self::first_arg_is_binary) is true, the first argument of each command is a binary argument.
+    /// Indices and spans in the parsed arguments are positions in the whole line. However argument, option and parameter
+    /// indices restart at zero for each command.
+    /// 
+    /// If successful, returns a success result holding an array of [commands](Command). Each command holds its parsed
+    /// arguments and the separator which connected it to the next command. Otherwise returns an error result containing the
+    /// [ParseError](ParseError) of the first command which failed to parse.
+    pub fn parse_commands<'a>(&'a self, line: &'a str) -> Result<Vec<Command<'a, O, P>>, ParseError> {
+        let segments = split_commands(self, line);
+        let mut commands = Vec::with_capacity(segments.len());
+        for segment in segments {
+            let (args, termination) = self.parse_line_range(line, segment.span.char_index, segment.span.byte_index, segment.span.end_byte_index)?;
+            commands.push(Command {
+                args,
+                span: segment.span,
+                separator: segment.separator,
+                separator_span: segment.separator_span,
+                termination,
+            });
+        }
+        Ok(commands)
+    }
+
+    /// Read commands from `reader` and parse them as they are read.
+    ///
+    /// Returns a [CommandReader] iterator which reads one line at a time from `reader` and yields each command in the line
+    /// (see [parse_commands](Self::parse_commands)) together with its line number.  Use [BufReader](std::io::BufReader) to read
+    /// from an [io::Read](std::io::Read) input.
+    pub fn read_commands<R: BufRead>(&self, reader: R) -> CommandReader<'_, R, O, P> {
+        CommandReader::new(self, reader)
+    }
+
+    /// Create a [ParseSession] which reuses buffers and results containers when parsing many lines with this parser.
+    pub fn new_session(&self) -> ParseSession<'_, O, P> {
+        ParseSession::new(self)
+    }
+
+    /// Split a command line into [tokens](crate::Token) according to the style of the Parser, without matching arguments.
+    /// 
+    /// Returns a [Tokenizer] iterator. Matchers are not used. See [Tokenizer] for more details.
+    pub fn tokenize<'a>(&'a self, line: &'a str) -> Tokenizer<'a, O, P> {
+        Tokenizer::new(self, line)
+    }
+
+    /// Get the [choices](crate::Matcher::choices) which can complete an option value.  Returns the choices of the matchers
+    /// with option codes matching `option_code` which start with `prefix`.  `prefix` is matched with
+    /// [option_values_case_sensitive](Self::option_values_case_sensitive).  This can be used for shell completion.
+    pub fn complete_option_value(&self, option_code: &str, prefix: &str) -> Vec<&str> {
+        let uppercase_prefix = prefix.to_uppercase();
+        let mut completions: Vec<&str> = Vec::new();
+        for matcher in &self.matchers {
+            if matcher.option_codes().is_some() && self.try_match_option_code(option_code, matcher.option_codes()) {
+                for choice in matcher.choices() {
+                    let is_completion = if self.option_values_case_sensitive {
+                        choice.starts_with(prefix)
+                    } else {
+                        choice.to_uppercase().starts_with(&uppercase_prefix)
+                    };
+                    if is_completion && !completions.contains(&choice.as_str()) {
+                        completions.push(choice);
+                    }
+                }
+            }
+        }
+        completions
+    }
+
+    fn parse_line_range<'a>(&'a self, line: &'a str, start_char_idx: usize, start_byte_idx: usize, end_byte_idx: usize)
+        -> Result<(Args<'a, O, P>, Option<Termination>), ParseError>
+    {
         let mut args = Vec::new();
+        let mut buffers = ParseBuffers::new();
+        let termination = self.parse_line_range_into(&mut Cow::Borrowed(line), start_char_idx, start_byte_idx, end_byte_idx, &mut buffers, &mut args)?;
+        Ok((args, termination))
+    }
+
+    /// Parse part of a line into `args` (which must be empty) using buffers from a previous parse. The line and buffers are
+    /// returned to `line` and `buffers` whether or not the parse succeeds, so an owned line can be parsed in several parts
+    /// without being copied.  Argument text can only borrow from `line` if it is borrowed.
+    pub(crate) fn parse_line_range_into<'a>(&'a self, line: &mut Cow<'a, str>, start_char_idx: usize, start_byte_idx: usize,
+        end_byte_idx: usize, buffers: &mut ParseBuffers, args: &mut Args<'a, O, P>) -> Result<Option<Termination>, ParseError>
+    {
+        let mut parse_state = ParseState::with_buffers(
+            mem::take(line),
+            self.first_arg_is_binary,
+            self.multi_char_option_code_requires_double_announcer,
+            mem::take(buffers),
+        );
+        let result = self.parse_line_state(&mut parse_state, start_char_idx, start_byte_idx, end_byte_idx, args);
+        (*line, *buffers) = parse_state.into_line_and_buffers();
+        result
+    }
+
+    fn parse_line_state<'a>(&'a self, parse_state: &mut ParseState<'a>, start_char_idx: usize, start_byte_idx: usize,
+        end_byte_idx: usize, args: &mut Args<'a, O, P>) -> Result<Option<Termination>, ParseError>
+    {
+        parse_state.line_len = end_byte_idx;
+        parse_state.line_or_env_arg_char_idx = start_char_idx;
+        parse_state.line_or_env_arg_byte_idx = start_byte_idx;
+        parse_state.env_line_approximate_char_idx = start_char_idx;
+
+        let mut termination: Option<Termination> = None;
+        while let Some(char) = parse_state.current_char() {
+            let env_char = EnvChar::Unicode(char);
+            let more = self.process_char(parse_state, env_char, args)?;
+
+            if more {
+                parse_state.increment_env_line_approximate_char_idx();
+                parse_state.increment_env_arg_char_idx(char);
+            } else {
+                // ignore rest of line
+                termination = Some(self.create_termination(parse_state, char));
+                break;
+            }
+        }
+
+        self.finalise_parse(parse_state, args)?;
+        self.finalise_pending_option_values(parse_state, args)?;
+        self.aggregate_counted_options(parse_state, args);
+        self.add_fallback_args(parse_state, args)?;
+        self.resolve_option_choices(args)?;
+        self.resolve_map_options(args)?;
+
+        Ok(termination)
+    }
+
+    fn visit_line<'a, V: ArgVisitor<'a, O, P>>(&'a self, line: &'a str, visitor: &mut V) -> Result<Option<Termination>, ParseError> {
+        let mut args = Vec::new();
+        let mut visited_matchers: Vec<&'a Matcher<O, P>> = Vec::new();
 
         let mut parse_state = ParseState::new(
-            line,
+            Cow::Borrowed(line),
             self.first_arg_is_binary,
             self.multi_char_option_code_requires_double_announcer,
         );
+        parse_state.defer_possible_option_values = true;
 
-        parse_state.line_or_env_arg_char_idx = 0;
-
-        for char in line.chars() {
+        let mut termination: Option<Termination> = None;
+        while let Some(char) = parse_state.current_char() {
             let env_char = EnvChar::Unicode(char);
             let more = self.process_char(&mut parse_state, env_char, &mut args)?;
+            self.match_deferred_option_value(&mut parse_state, visitor, &mut args)?;
+            if self.visit_completed_args(&mut parse_state, &mut args, &mut visited_matchers, visitor)?.is_break() {
+                return Ok(None);
+            }
 
             if more {
                 parse_state.increment_env_line_approximate_char_idx();
-                parse_state.increment_env_arg_char_idx();
+                parse_state.increment_env_arg_char_idx(char);
             } else {
                 // ignore rest of line
+                termination = Some(self.create_termination(&parse_state, char));
                 break;
             }
         }
 
         self.finalise_parse(&mut parse_state, &mut args)?;
+        self.match_deferred_option_value(&mut parse_state, visitor, &mut args)?;
+        self.finalise_pending_option_values(&mut parse_state, &args)?;
+        if self.visit_completed_args(&mut parse_state, &mut args, &mut visited_matchers, visitor)?.is_break() {
+            return Ok(None);
+        }
 
-        Ok(args)
+        self.add_unmatched_fallback_args(&mut parse_state, &visited_matchers, &mut args)?;
+        if self.visit_completed_args(&mut parse_state, &mut args, &mut visited_matchers, visitor)?.is_break() {
+            return Ok(None);
+        }
+
+        Ok(termination)
     }
 
-    fn process_char<'a>(&'a self, parse_state: &mut ParseState, env_char: EnvChar, args: &mut Args<'a, O, P>) -> Result<bool, ParseError> {
+    /// Ask the visitor whether a deferred possible option value is the option's value or a parameter and match it accordingly.
+    fn match_deferred_option_value<'a, V: ArgVisitor<'a, O, P>>(&'a self, parse_state: &mut ParseState<'a>, visitor: &mut V,
+        args: &mut Args<'a, O, P>) -> Result<(), ParseError>
+    {
+        if parse_state.possible_option_value_deferred {
+            parse_state.possible_option_value_deferred = false;
+            if visitor.is_option_value(&parse_state.option_code, &parse_state.value_bldr) {
+                self.match_expanded_option_arg(parse_state, true, args)
+            } else {
+                self.match_option_value_as_param(parse_state, args)
+            }
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Pass the completed arguments to the visitor and remove them from `args`.
+    fn visit_completed_args<'a, V: ArgVisitor<'a, O, P>>(&'a self, parse_state: &mut ParseState<'a>, args: &mut Args<'a, O, P>,
+        visited_matchers: &mut Vec<&'a Matcher<O, P>>, visitor: &mut V) -> Result<ControlFlow<()>, ParseError>
+    {
+        let completed_count = self.prepare_completed_args(parse_state, args, visited_matchers)?;
+        for arg in args.drain(..completed_count) {
+            let flow = match arg {
+                Arg::Binary(properties) => visitor.on_binary(properties),
+                Arg::Option(properties) => visitor.on_option(properties),
+                Arg::Param(properties) => visitor.on_param(properties),
+            };
+            if flow.is_break() {
+                return Ok(flow);
+            }
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+
+    /// Resolves the arguments at the start of `args` which are complete, records their matchers in `matched_matchers` and returns
+    /// how many there are.  An option still waiting for value arguments and the arguments after it are not complete.  The caller
+    /// must then remove the completed arguments from `args`.
+    pub(crate) fn prepare_completed_args<'a>(&self, parse_state: &mut ParseState<'a>, args: &mut Args<'a, O, P>,
+        matched_matchers: &mut Vec<&'a Matcher<O, P>>) -> Result<usize, ParseError>
+    {
+        let completed_count = parse_state.pending_values_arg_idx.unwrap_or(args.len());
+        if completed_count > 0 {
+            self.resolve_option_choices(&mut args[..completed_count])?;
+            self.resolve_map_options(&mut args[..completed_count])?;
+            parse_state.pending_values_arg_idx = parse_state.pending_values_arg_idx.map(|arg_idx| arg_idx - completed_count);
+            matched_matchers.extend(args[..completed_count].iter().map(arg_matcher));
+        }
+        Ok(completed_count)
+    }
+
+    pub(crate) fn process_char<'a>(&'a self, parse_state: &mut ParseState<'a>, env_char: EnvChar, args: &mut Args<'a, O, P>) -> Result<bool, ParseError> {
         match parse_state.arg_parse_state {
             ArgParseState::WaitBinary => {
                 if let Some(unicode_char) = env_char.try_get_unicode_non_whitespace() {
                     if self.parse_terminate_chars.contains(&unicode_char) {
                         Ok(false)
                     } else {
-                        self.initialise_param_parsing(parse_state, unicode_char, true);
                         parse_state.arg_parse_state = ArgParseState::InParam;
+                        self.initialise_param_parsing(parse_state, unicode_char, true);
                         Ok(true)
                     }
                 } else {
@@ -664,6 +1177,7 @@ impl<O: Default, P: Default> Parser<O, P> {
                         Ok(false)
                     } else {
                         if self.option_announcer_chars.contains(&unicode_char) {
+                            self.finalise_pending_option_values(parse_state, args)?;
                             parse_state.arg_parse_state = ArgParseState::InOption;
                             self.initialise_option_parsing(parse_state, unicode_char);
                         } else {
@@ -746,8 +1260,8 @@ impl<O: Default, P: Default> Parser<O, P> {
                         Err(parse_state.create_param_error(ParseErrorTypeId::EscapeCharacterAtEndOfParam))?;
                     }
                     EnvChar::Unicode(unicode_char) => {
-                        if self.can_char_be_escaped(parse_state, unicode_char) {
-                            parse_state.value_bldr.push(unicode_char);
+                        if self.can_char_be_escaped(unicode_char, parse_state.value_quote_char()) {
+                            parse_state.push_escaped_value_char(unicode_char);
                             parse_state.arg_parse_state = ArgParseState::InParam;
                         } else {
                             Err(parse_state.create_param_error(ParseErrorTypeId::EscapedCharacterInParamCannotBeEscaped))?;
@@ -837,6 +1351,8 @@ impl<O: Default, P: Default> Parser<O, P> {
                                     }
                                 }
 
+                                let at_element_start = parse_state.value_at_element_start;
+                                parse_state.value_at_element_start = false;
                                 if parse_state.option_parse_state == OptionParseState::InValue {
                                     if parse_state.value_quoted {
                                         if unicode_char == parse_state.arg_quote_char {
@@ -846,7 +1362,17 @@ impl<O: Default, P: Default> Parser<O, P> {
                                         }
                                     } else {
                                         if !unicode_char.is_whitespace() {
-                                            parse_state.value_bldr.push(unicode_char);
+                                            if at_element_start && self.quote_chars.contains(&unicode_char) {
+                                                // quoted element in a delimited value list
+                                                parse_state.value_quoted = true;
+                                                parse_state.arg_quote_char = unicode_char;
+                                            } else {
+                                                if self.is_option_value_delimiter(parse_state, unicode_char) {
+                                                    parse_state.push_value_delimiter(unicode_char);
+                                                } else {
+                                                    parse_state.value_bldr.push(unicode_char);
+                                                }
+                                            }
                                         } else {
                                             self.match_option_arg(parse_state, true, args)?;
                                             parse_state.arg_parse_state = ArgParseState::WaitOptionOrParam;
@@ -873,7 +1399,14 @@ impl<O: Default, P: Default> Parser<O, P> {
                                         self.match_option_arg(parse_state, true, args)?;
                                         parse_state.arg_parse_state = ArgParseState::WaitOptionOrParam;
                                     } else {
-                                        Err(parse_state.create_option_error(ParseErrorTypeId::QuotedOptionValueNotFollowedByWhitespaceChar))?;
+                                        if self.is_option_value_delimiter(parse_state, unicode_char) {
+                                            // quoted element in a delimited value list is followed by another element
+                                            parse_state.push_value_delimiter(unicode_char);
+                                            parse_state.value_quoted = false;
+                                            parse_state.option_parse_state = OptionParseState::InValue;
+                                        } else {
+                                            Err(parse_state.create_option_error(ParseErrorTypeId::QuotedOptionValueNotFollowedByWhitespaceChar))?;
+                                        }
                                     }
                                 }
                             },
@@ -886,8 +1419,8 @@ impl<O: Default, P: Default> Parser<O, P> {
                                 Err(parse_state.create_option_error(ParseErrorTypeId::EscapeCharacterAtEndOfOptionValue))?;
                             }
                             EnvChar::Unicode(unicode_char) => {
-                                if self.can_char_be_escaped(parse_state, unicode_char) {
-                                    parse_state.value_bldr.push(unicode_char);
+                                if self.can_char_be_escaped(unicode_char, parse_state.value_quote_char()) {
+                                    parse_state.push_escaped_value_char(unicode_char);
                                     parse_state.option_parse_state = OptionParseState::InValue;
                                 } else {
                                     Err(parse_state.create_option_error(ParseErrorTypeId::EscapedCharacterInOptionValueCannotBeEscaped))?;
@@ -901,8 +1434,90 @@ impl<O: Default, P: Default> Parser<O, P> {
         }
     }
 
-    fn finalise_option_code<'a>(&'a self, parse_state: &mut ParseState, value_announced: ValueAnnounced, args: &mut Args<'a, O, P>)  -> Result<(), ParseError> {
-        parse_state.set_option_code(Some(parse_state.line_or_env_arg_char_idx))?;
+    fn set_option_code(&self, parse_state: &mut ParseState, optional_ending_byte_index: Option<usize>) -> Result<(), ParseError> {
+        match parse_state.set_option_code(optional_ending_byte_index) {
+            Ok(()) => self.expand_abbreviated_option_code(parse_state),
+            Err(error) if matches!(error.type_id, ParseErrorTypeId::OptionCodeMissingDoubleAnnouncer) => {
+                // clustered counted options (eg. -vvv) only have one announcer
+                let code_is_clustered = self.option_code_matchers(&parse_state.option_code)
+                    .any(|matcher| self.try_match_clustered_option_code(&parse_state.option_code, matcher).is_some());
+                if code_is_clustered {
+                    Ok(())
+                } else {
+                    Err(error)
+                }
+            }
+            result => result,
+        }
+    }
+
+    /// Whether the option code currently being parsed may be an abbreviation.  If a double announcer is required for multi
+    /// character option codes, only double announced option codes can be abbreviated (so `-o` is not expanded to `--output`).
+    /// Otherwise single character option codes are not abbreviations.
+    fn can_option_code_be_abbreviation(&self, parse_state: &ParseState) -> bool {
+        if parse_state.multi_char_option_code_requires_double_announcer {
+            parse_state.option_code_is_double_announced()
+        } else {
+            parse_state.option_code.chars().nth(1).is_some()
+        }
+    }
+
+    /// If option code abbreviations are enabled and the option code does not match a matcher, replaces the option code with
+    /// the matcher option code which it uniquely abbreviates.
+    fn expand_abbreviated_option_code(&self, parse_state: &mut ParseState) -> Result<(), ParseError> {
+        if self.option_code_abbreviations && !parse_state.option_code.is_empty() && self.can_option_code_be_abbreviation(parse_state) {
+            let matched = self.option_code_matchers(&parse_state.option_code).any(|matcher| self.try_match_option_excluding_value(parse_state, matcher));
+            if !matched {
+                let abbreviation = std::mem::take(&mut parse_state.option_code);
+                let mut candidates: Vec<String> = Vec::new();
+                for matcher in &self.matchers {
+                    if let Some(matcher_codes) = matcher.option_codes() {
+                        for matcher_code in matcher_codes {
+                            if self.is_option_code_abbreviation(&abbreviation, matcher_code) {
+                                // check rest of matcher accepts full code
+                                parse_state.option_code = String::from(matcher_code.text());
+                                if self.try_match_option_excluding_value(parse_state, matcher) {
+                                    if !candidates.contains(&parse_state.option_code) {
+                                        candidates.push(parse_state.option_code.clone());
+                                    }
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                }
+
+                match candidates.len() {
+                    0 => parse_state.option_code = abbreviation,
+                    1 => parse_state.option_code = candidates.remove(0),
+                    _ => {
+                        parse_state.option_code = abbreviation;
+                        let mut error = parse_state.create_option_error(ParseErrorTypeId::AmbiguousOption);
+                        error.context.candidates = candidates;
+                        return Err(error);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn is_option_code_abbreviation(&self, abbreviation: &str, matcher_code: &RegexOrText) -> bool {
+        if matcher_code.is_regex() || matcher_code.text().len() <= abbreviation.len() {
+            false
+        } else {
+            let case_sensitive = matcher_code.override_case_sensitive().unwrap_or(self.option_codes_case_sensitive);
+            if case_sensitive {
+                matcher_code.text().starts_with(abbreviation)
+            } else {
+                matcher_code.text().to_uppercase().starts_with(&abbreviation.to_uppercase())
+            }
+        }
+    }
+
+    fn finalise_option_code<'a>(&'a self, parse_state: &mut ParseState<'a>, value_announced: ValueAnnounced, args: &mut Args<'a, O, P>)  -> Result<(), ParseError> {
+        self.set_option_code(parse_state, Some(parse_state.line_or_env_arg_byte_idx))?;
+        parse_state.option_value_announcer_is_ambiguous = matches!(value_announced, ValueAnnounced::Ambiguous);
         match value_announced {
             ValueAnnounced::Definitely => {
                 if self.can_option_code_have_value(parse_state) {
@@ -931,7 +1546,7 @@ impl<O: Default, P: Default> Parser<O, P> {
         }
     }
 
-    fn finalise_parse<'a>(&'a self, parse_state: &mut ParseState, args: &mut Args<'a, O, P>) -> Result<(), ParseError> {
+    pub(crate) fn finalise_parse<'a>(&'a self, parse_state: &mut ParseState<'a>, args: &mut Args<'a, O, P>) -> Result<(), ParseError> {
         match parse_state.arg_parse_state {
             ArgParseState::WaitBinary => {
                 Ok(())
@@ -960,7 +1575,7 @@ impl<O: Default, P: Default> Parser<O, P> {
             ArgParseState::InOption => {
                 match parse_state.option_parse_state {
                     OptionParseState::InCode => {
-                        parse_state.set_option_code(None)?;
+                        self.set_option_code(parse_state, None)?;
                         parse_state.current_option_value_may_be_param = false;
                         self.match_option_arg(parse_state, false, args)
                     }
@@ -998,7 +1613,311 @@ impl<O: Default, P: Default> Parser<O, P> {
         }
     }
 
-    fn can_char_be_escaped(&self, parse_state: &mut ParseState, unicode_char: char) -> bool {
+    /// Merges repeated options matched by a [counted](Matcher::counted) matcher into the first occurrence and renumbers the
+    /// argument and option indices of the remaining arguments.  The parse state's argument and option counts are reduced by
+    /// the number of merged options so that arguments added afterwards (eg. fallbacks) continue the numbering.
+    fn aggregate_counted_options(&self, parse_state: &mut ParseState, args: &mut Args<'_, O, P>) {
+        if self.matchers.iter().any(|matcher| matcher.counted()) {
+            let mut first_occurrence_idxs: Vec<(usize, usize)> = Vec::new(); // (matcher index, arg index)
+            let mut keep_args = vec![true; args.len()];
+            for arg_idx in 0..args.len() {
+                if let Arg::Option(properties) = &args[arg_idx] {
+                    if properties.matcher.counted() {
+                        let matcher_idx = properties.matcher.index();
+                        match first_occurrence_idxs.iter().find(|(first_matcher_idx, _)| *first_matcher_idx == matcher_idx) {
+                            None => first_occurrence_idxs.push((matcher_idx, arg_idx)),
+                            Some(&(_, first_idx)) => {
+                                let count = properties.count;
+                                if let Arg::Option(first_properties) = &mut args[first_idx] {
+                                    first_properties.count += count;
+                                }
+                                keep_args[arg_idx] = false;
+                            }
+                        }
+                    }
+                }
+            }
+
+            let merged_count = keep_args.iter().filter(|keep| !**keep).count();
+            if merged_count > 0 {
+                let mut keep_args_iter = keep_args.into_iter();
+                args.retain(|_| keep_args_iter.next().unwrap_or(true));
+
+                let mut option_idx = 0;
+                for (arg_idx, arg) in args.iter_mut().enumerate() {
+                    match arg {
+                        Arg::Binary(properties) => properties.arg_index = arg_idx,
+                        Arg::Param(properties) => properties.arg_index = arg_idx,
+                        Arg::Option(properties) => {
+                            properties.arg_index = arg_idx;
+                            properties.option_index = option_idx;
+                            option_idx += 1;
+                        }
+                    }
+                }
+
+                parse_state.arg_count -= merged_count;
+                parse_state.option_count -= merged_count;
+            }
+        }
+    }
+
+    /// Replaces the values of options whose matcher has choices with the choice's spelling. Returns an error if a value is not
+    /// one of the choices.  The choices of map options are applied to the pair values in
+    /// [resolve_map_options](Self::resolve_map_options).
+    fn resolve_option_choices(&self, args: &mut [Arg<'_, O, P>]) -> Result<(), ParseError> {
+        if self.matchers.iter().any(|matcher| !matcher.choices().is_empty()) {
+            for arg in args.iter_mut() {
+                if let Arg::Option(properties) = arg {
+                    let matcher = properties.matcher;
+                    if !matcher.choices().is_empty() && matcher.map_separator().is_none() && properties.value_text.is_some() {
+                        let mut values = Vec::with_capacity(properties.values.len());
+                        for value in &properties.values {
+                            match self.find_choice(matcher, value) {
+                                Some(choice) => values.push(Cow::Borrowed(choice)),
+                                None => {
+                                    let mut error = self.create_option_properties_error(ParseErrorTypeId::InvalidChoice, properties);
+                                    error.param_value_text = value.to_string();
+                                    error.context.candidates = Vec::from(matcher.choices());
+                                    return Err(error);
+                                }
+                            }
+                        }
+
+                        let value_text = match matcher.value_delimiter() {
+                            Some(delimiter) if *matcher.option_value_arity() == OptionValueArity::Single => {
+                                Cow::Owned(values.join(&String::from(*delimiter)))
+                            }
+                            _ => values[0].clone(),
+                        };
+                        properties.value_text = Some(value_text);
+                        properties.values = values;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn find_choice<'a>(&self, matcher: &'a Matcher<O, P>, value: &str) -> Option<&'a str> {
+        matcher.choices().iter().find(|choice| {
+            if self.option_values_case_sensitive {
+                *choice == value
+            } else {
+                choice.to_uppercase() == value.to_uppercase()
+            }
+        }).map(|choice| choice.as_str())
+    }
+
+    /// Splits the values of map options into key/value pairs, validates the keys, resolves the pair values against the matcher's
+    /// choices and applies the matcher's duplicates policy.
+    fn resolve_map_options(&self, args: &mut [Arg<'_, O, P>]) -> Result<(), ParseError> {
+        if self.matchers.iter().any(|matcher| matcher.map_separator().is_some()) {
+            let mut pair_locations: Vec<(usize, String, usize)> = Vec::new(); // (matcher index, key, arg index)
+            let mut superseded_pairs: Vec<(usize, String)> = Vec::new(); // (arg index, key) in earlier args
+            for (arg_idx, arg) in args.iter_mut().enumerate() {
+                if let Arg::Option(properties) = arg {
+                    if let Some(separator) = *properties.matcher.map_separator() {
+                        let matcher = properties.matcher;
+                        let mut pairs: Vec<(String, String)> = Vec::with_capacity(properties.values.len());
+                        for value in &properties.values {
+                            let (key, pair_value) = value.split_once(separator).unwrap_or((value, ""));
+                            if key.is_empty() || !self.try_match_value_text(key, matcher.map_key(), self.option_values_case_sensitive) {
+                                return Err(self.create_option_properties_error(ParseErrorTypeId::InvalidMapKey, properties));
+                            }
+                            let pair_value = if matcher.choices().is_empty() {
+                                pair_value
+                            } else {
+                                match self.find_choice(matcher, pair_value) {
+                                    Some(choice) => choice,
+                                    None => {
+                                        let mut error = self.create_option_properties_error(ParseErrorTypeId::InvalidChoice, properties);
+                                        error.param_value_text = String::from(pair_value);
+                                        error.context.candidates = Vec::from(matcher.choices());
+                                        return Err(error);
+                                    }
+                                }
+                            };
+
+                            let existing_location_idx = pair_locations.iter().position(|(matcher_idx, existing_key, _)|
+                                *matcher_idx == matcher.index() && existing_key == key
+                            );
+                            match (existing_location_idx, matcher.map_duplicates()) {
+                                (Some(_), MapDuplicates::Error) => {
+                                    return Err(self.create_option_properties_error(ParseErrorTypeId::DuplicateMapKey, properties));
+                                }
+                                (Some(location_idx), MapDuplicates::LastWins) => {
+                                    let (_, _, existing_arg_idx) = pair_locations.remove(location_idx);
+                                    if existing_arg_idx == arg_idx {
+                                        pairs.retain(|(existing_key, _)| existing_key != key);
+                                    } else {
+                                        superseded_pairs.push((existing_arg_idx, String::from(key)));
+                                    }
+                                }
+                                _ => {}
+                            }
+
+                            pair_locations.push((matcher.index(), String::from(key), arg_idx));
+                            pairs.push((String::from(key), String::from(pair_value)));
+                        }
+                        properties.pairs = pairs;
+                    }
+                }
+            }
+
+            for (arg_idx, key) in superseded_pairs {
+                if let Arg::Option(properties) = &mut args[arg_idx] {
+                    properties.pairs.retain(|(existing_key, _)| *existing_key != key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn add_fallback_args<'a>(&'a self, parse_state: &mut ParseState<'a>, args: &mut Args<'a, O, P>) -> Result<(), ParseError> {
+        let matched_matchers: Vec<&Matcher<O, P>> = args.iter().map(arg_matcher).collect();
+        self.add_unmatched_fallback_args(parse_state, &matched_matchers, args)
+    }
+
+    /// Adds fallback arguments for the matchers which are not in `matched_matchers`.
+    pub(crate) fn add_unmatched_fallback_args<'a>(&'a self, parse_state: &mut ParseState<'a>, matched_matchers: &[&Matcher<O, P>],
+        args: &mut Args<'a, O, P>) -> Result<(), ParseError>
+    {
+        self.check_config_file_keys(parse_state)?;
+
+        for matcher in &self.matchers {
+            let matched = matched_matchers.iter().any(|matched_matcher| std::ptr::eq(*matched_matcher, matcher));
+
+            if !matched {
+                if let Some((value_text, value_source)) = self.find_fallback_value(matcher) {
+                    self.add_fallback_arg(parse_state, matcher, value_text, value_source, args)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_config_file_keys(&self, parse_state: &ParseState) -> Result<(), ParseError> {
+        for config_file in &self.config_files {
+            for entry in config_file.entries() {
+                if !self.matchers.iter().any(|matcher| matcher.name() == entry.key) {
+                    let span = parse_state.span_from(parse_state.line_or_env_arg_char_idx, parse_state.line_or_env_arg_byte_idx);
+                    return Err(ParseError::new_config(ParseErrorTypeId::UnknownConfigKey, parse_state.env_line_approximate_char_idx, span,
+                        parse_state.arg_count, &entry.key, ValueSource::ConfigFile(config_file.location(entry))));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Looks up a fallback value for a matcher. Precedence is: environment variable, config files (later files override
+    /// earlier files), default value.
+    fn find_fallback_value(&self, matcher: &Matcher<O, P>) -> Option<(String, ValueSource)> {
+        if let Some(env_value) = matcher.env_var_name().as_ref().and_then(|name| self.env_var_resolver.resolve(name)) {
+            return Some((env_value, ValueSource::Environment));
+        }
+
+        for config_file in self.config_files.iter().rev() {
+            if let Some(entry) = config_file.find_entry(matcher.name()) {
+                return Some((entry.value.clone(), ValueSource::ConfigFile(config_file.location(entry))));
+            }
+        }
+
+        matcher.default_value().as_ref().map(|default_value| (default_value.clone(), ValueSource::Default))
+    }
+
+    fn add_fallback_arg<'a>(&self, parse_state: &mut ParseState, matcher: &'a Matcher<O, P>, value_text: String, value_source: ValueSource,
+        args: &mut Args<'a, O, P>
+    ) -> Result<(), ParseError> {
+        let is_param = match matcher.arg_type() {
+            Some(MatchArgTypeId::Param) => true,
+            Some(MatchArgTypeId::Option) => false,
+            None => matcher.option_codes().is_none(),
+        };
+        let span = parse_state.span_from(parse_state.line_or_env_arg_char_idx, parse_state.line_or_env_arg_byte_idx);
+
+        let is_flag = !is_param && *matcher.effective_option_has_value() == OptionHasValue::Never;
+        let flag_enabled = if is_flag { parse_flag_value(&value_text) } else { None };
+
+        let value_is_valid = if is_param {
+            self.try_match_value_text(&value_text, matcher.value_text(), self.params_case_sensitive)
+        } else {
+            if is_flag {
+                flag_enabled.is_some()
+            } else {
+                self.try_match_value_text(&value_text, matcher.value_text(), self.option_codes_case_sensitive)
+            }
+        };
+        if !value_is_valid {
+            let mut error = if is_param {
+                ParseError::new_param(ParseErrorTypeId::InvalidFallbackValue, parse_state.env_line_approximate_char_idx, span, "",
+                    parse_state.arg_count, parse_state.param_count, &value_text)
+            } else {
+                ParseError::new_option(ParseErrorTypeId::InvalidFallbackValue, parse_state.env_line_approximate_char_idx, span, "",
+                    parse_state.arg_count, parse_state.option_count, matcher.first_text_option_code().unwrap_or(""), &value_text)
+            };
+            error.context.value_source = Some(value_source);
+            return Err(error);
+        }
+
+        if is_param {
+            let properties = ParamProperties {
+                matcher,
+                char_index: span.char_index,
+                env_line_approximate_char_index: parse_state.env_line_approximate_char_idx,
+                span,
+                raw_text: Cow::Borrowed(""),
+                arg_index: parse_state.arg_count,
+                env_arg_index: parse_state.env_arg_idx,
+                param_index: parse_state.param_count,
+                value_text: Cow::Owned(value_text),
+                value_source,
+                captures_case_sensitive: self.params_case_sensitive,
+            };
+            args.push(Arg::Param(properties));
+            parse_state.param_count += 1;
+        } else {
+            // a flag's fallback value only specifies whether it is enabled
+            let (value_text, values) = if is_flag {
+                (None, Vec::new())
+            } else {
+                let values = match matcher.value_delimiter() {
+                    Some(delimiter) => value_text.split(*delimiter).map(|value| Cow::Owned(String::from(value))).collect(),
+                    None => vec![Cow::Owned(value_text.clone())],
+                };
+                (Some(Cow::Owned(value_text)), values)
+            };
+            let properties = OptionProperties {
+                matcher,
+                char_index: span.char_index,
+                env_line_approximate_char_index: parse_state.env_line_approximate_char_idx,
+                span,
+                raw_text: Cow::Borrowed(""),
+                arg_index: parse_state.arg_count,
+                env_arg_index: parse_state.env_arg_idx,
+                option_index: parse_state.option_count,
+                code: Cow::Borrowed(matcher.first_text_option_code().unwrap_or("")),
+                code_span: span,
+                value_text,
+                value_span: None,
+                value_raw_text: None,
+                values,
+                pairs: Vec::new(),
+                value_source,
+                enabled: flag_enabled.unwrap_or(true),
+                announcer_char: self.option_announcer_chars.first().copied().unwrap_or('-'),
+                count: 1,
+                captures_case_sensitive: self.option_codes_case_sensitive,
+            };
+            args.push(Arg::Option(properties));
+            parse_state.option_count += 1;
+        }
+
+        parse_state.arg_count += 1;
+        Ok(())
+    }
+
+    pub(crate) fn can_char_be_escaped(&self, unicode_char: char, value_quote_char: Option<char>) -> bool {
         for escapeable_logical_char in &self.escapeable_logical_chars {
             match escapeable_logical_char {
                 EscapeableLogicalChar::Escape => {
@@ -1009,7 +1928,7 @@ impl<O: Default, P: Default> Parser<O, P> {
                     }
                 }
                 EscapeableLogicalChar::Quote => {
-                    if parse_state.value_quoted && unicode_char == parse_state.arg_quote_char {
+                    if Some(unicode_char) == value_quote_char {
                         return true;
                     }
                 }
@@ -1042,34 +1961,66 @@ impl<O: Default, P: Default> Parser<O, P> {
         false
     }
 
+    pub(crate) fn create_termination(&self, parse_state: &ParseState, terminate_char: char) -> Termination {
+        let char_idx = parse_state.line_or_env_arg_char_idx;
+        let byte_idx = parse_state.line_or_env_arg_byte_idx;
+        let remainder_byte_idx = byte_idx + terminate_char.len_utf8();
+        let remainder = &parse_state.line_or_env_arg[remainder_byte_idx..parse_state.line_len];
+        Termination {
+            terminate_char,
+            span: Span::new(char_idx, char_idx + 1, byte_idx, remainder_byte_idx),
+            env_line_approximate_char_index: parse_state.env_line_approximate_char_idx,
+            env_arg_index: parse_state.env_arg_idx,
+            remainder: String::from(remainder),
+            remainder_span: Span::new(char_idx + 1, char_idx + 1 + remainder.chars().count(), remainder_byte_idx, parse_state.line_len),
+            remaining_env_args: Vec::new(),
+        }
+    }
+
     fn initialise_option_parsing(&self, parse_state: &mut ParseState, unicode_char: char) {
         parse_state.option_parse_state = OptionParseState::InCode;
         parse_state.option_announcer_char = unicode_char;
         parse_state.arg_start_char_idx = parse_state.line_or_env_arg_char_idx;
+        parse_state.arg_start_byte_idx = parse_state.line_or_env_arg_byte_idx;
         parse_state.arg_start_env_line_approximate_char_idx = parse_state.env_line_approximate_char_idx;
-        parse_state.option_code_start_line_char_idx = parse_state.line_or_env_arg_char_idx + 1;
+        parse_state.option_code_start_byte_idx = parse_state.line_or_env_arg_byte_idx + unicode_char.len_utf8();
+        parse_state.option_env_arg_idx = parse_state.env_arg_idx;
     }
 
     fn initialise_param_parsing(&self, parse_state: &mut ParseState, unicode_char: char, is_binary: bool) {
-        parse_state.value_bldr.clear();
+        parse_state.clear_value();
         parse_state.arg_start_char_idx = parse_state.line_or_env_arg_char_idx;
+        parse_state.arg_start_byte_idx = parse_state.line_or_env_arg_byte_idx;
         parse_state.arg_start_env_line_approximate_char_idx = parse_state.env_line_approximate_char_idx;
         parse_state.value_quoted = self.quote_chars.contains(&unicode_char);
         if parse_state.value_quoted {
             parse_state.arg_quote_char = unicode_char;
         } else {
-            parse_state.value_bldr.push(unicode_char);
+            if Some(unicode_char) == self.escape_char {
+                parse_state.arg_parse_state = ArgParseState::InParamEscaped;
+            } else {
+                parse_state.value_bldr.push(unicode_char);
+            }
         }
         parse_state.current_param_is_binary = is_binary;
     }
 
     fn initialise_option_value_parsing(&self, parse_state: &mut ParseState, unicode_char: char) {
-        parse_state.value_bldr.clear();
+        parse_state.clear_value();
+        self.resolve_option_value_delimiters(parse_state);
+        parse_state.value_start_char_idx = parse_state.line_or_env_arg_char_idx;
+        parse_state.value_start_byte_idx = parse_state.line_or_env_arg_byte_idx;
+        parse_state.value_start_env_line_approximate_char_idx = parse_state.env_line_approximate_char_idx;
+        parse_state.value_start_env_arg_idx = parse_state.env_arg_idx;
         parse_state.value_quoted = self.quote_chars.contains(&unicode_char);
         if parse_state.value_quoted {
             parse_state.arg_quote_char = unicode_char;
         } else {
-            parse_state.value_bldr.push(unicode_char);
+            if Some(unicode_char) == self.escape_char {
+                parse_state.option_parse_state = OptionParseState::InValueEscaped;
+            } else {
+                parse_state.value_bldr.push(unicode_char);
+            }
         }
     }
 
@@ -1077,7 +2028,7 @@ impl<O: Default, P: Default> Parser<O, P> {
         if self.matchers.is_empty() {
             self.can_option_code_have_value_with_matcher(parse_state, &self.any_matcher)
         } else {
-            for matcher in &self.matchers {
+            for matcher in self.option_code_matchers(&parse_state.option_code) {
                 if self.can_option_code_have_value_with_matcher(parse_state, matcher) {
                     return true
                 }
@@ -1088,7 +2039,7 @@ impl<O: Default, P: Default> Parser<O, P> {
 
     fn can_option_code_have_value_with_matcher(&self, parse_state: &ParseState, matcher: &Matcher<O, P>) -> bool {
         if self.try_match_option_excluding_value(parse_state, matcher) {
-            *matcher.option_has_value() != OptionHasValue::Never
+            *matcher.effective_option_has_value() != OptionHasValue::Never
         } else { 
             false
         }
@@ -1100,7 +2051,7 @@ impl<O: Default, P: Default> Parser<O, P> {
             self.can_option_have_value_with_first_char_with_matcher(parse_state, first_char_of_value_is_option_announcer, &self.any_matcher)
         } else {
             has_value = OptionHasValueBasedOnFirstChar::MustNot;
-            for matcher in &self.matchers {
+            for matcher in self.option_code_matchers(&parse_state.option_code) {
                 let matched_has_value = self.can_option_have_value_with_first_char_with_matcher(parse_state, first_char_of_value_is_option_announcer, matcher)?;
                 match matched_has_value {
                     OptionHasValueBasedOnFirstChar::Must => return Ok(OptionHasValueBasedOnFirstChar::Must),
@@ -1117,7 +2068,7 @@ impl<O: Default, P: Default> Parser<O, P> {
         matcher: &Matcher<O, P>
     ) -> Result<OptionHasValueBasedOnFirstChar, ParseError> {
         if self.try_match_option_excluding_value(parse_state, matcher) {
-            match *matcher.option_has_value() {
+            match *matcher.effective_option_has_value() {
                 OptionHasValue::Always => {
                     if matcher.option_value_can_start_with_option_announcer() {
                         Ok(OptionHasValueBasedOnFirstChar::Must)
@@ -1153,44 +2104,193 @@ impl<O: Default, P: Default> Parser<O, P> {
         }
     }
 
-    fn match_option_arg<'a>(&'a self, parse_state: &mut ParseState, has_value: bool, args: &mut Args<'a, O, P>) -> Result<(), ParseError> {
-        let mut optioned_matcher = self.try_find_option_matcher(parse_state, has_value);
+    fn match_option_arg<'a>(&'a self, parse_state: &mut ParseState<'a>, has_value: bool, args: &mut Args<'a, O, P>) -> Result<(), ParseError> {
+        if has_value {
+            self.expand_value_variables(parse_state);
+        }
+        if has_value && parse_state.current_option_value_may_be_param && parse_state.defer_possible_option_values {
+            // a visitor decides whether the value is a parameter
+            parse_state.possible_option_value_deferred = true;
+            Ok(())
+        } else {
+            self.match_expanded_option_arg(parse_state, has_value, args)
+        }
+    }
+
+    fn match_expanded_option_arg<'a>(&'a self, parse_state: &mut ParseState<'a>, has_value: bool, args: &mut Args<'a, O, P>)
+        -> Result<(), ParseError>
+    {
+        let optioned_matcher = self.try_find_option_matcher(parse_state, has_value);
         if let Some(matcher) = optioned_matcher {
             self.add_option_arg(parse_state, has_value, matcher, args);
             Ok(())
         } else {
             if has_value && parse_state.current_option_value_may_be_param {
                 // Ambiguous value announcer (white space). Value may have been a parameter.
-                // Try matching option without value and then add subsequent arg as parameter.
-                optioned_matcher = self.try_find_option_matcher(parse_state, false);
-                if let Some(matcher) = optioned_matcher {
-                    self.add_option_arg(parse_state, false, matcher, args);
-                    self.match_param_arg(parse_state, args)?;
-                    Ok(())
-                } else {
-                    Err(parse_state.create_option_error(ParseErrorTypeId::UnmatchedOption))
-                }
+                self.match_option_value_as_param(parse_state, args)
             } else {
                 Err(parse_state.create_option_error(ParseErrorTypeId::UnmatchedOption))
             }
         }
     }
 
-    fn add_option_arg<'a>(&self, parse_state: &mut ParseState, has_value: bool, matcher: &'a Matcher<O, P>, args: &mut Args<'a, O, P>) {
-        let value_text = if has_value {
-            Some(parse_state.value_bldr.clone())
+    /// Match the option without a value and then add the value as a parameter.
+    fn match_option_value_as_param<'a>(&'a self, parse_state: &mut ParseState<'a>, args: &mut Args<'a, O, P>) -> Result<(), ParseError> {
+        let optioned_matcher = self.try_find_option_matcher(parse_state, false);
+        if let Some(matcher) = optioned_matcher {
+            self.add_option_arg(parse_state, false, matcher, args);
+            parse_state.arg_start_char_idx = parse_state.value_start_char_idx;
+            parse_state.arg_start_byte_idx = parse_state.value_start_byte_idx;
+            parse_state.arg_start_env_line_approximate_char_idx = parse_state.value_start_env_line_approximate_char_idx;
+            parse_state.current_param_is_binary = false;
+            self.match_param_arg(parse_state, args)
         } else {
-            None
+            Err(parse_state.create_option_error(ParseErrorTypeId::UnmatchedOption))
+        }
+    }
+
+    fn expand_value_variables(&self, parse_state: &mut ParseState) {
+        if !parse_state.value_variables_expanded && !self.variable_syntaxes.is_empty() {
+            let literal = parse_state.value_quoted && self.variable_literal_quote_chars.contains(&parse_state.arg_quote_char);
+            if !literal {
+                if parse_state.value_delimiter_byte_idxs.is_empty() {
+                    parse_state.value_bldr = expand_variables(&parse_state.value_bldr, &parse_state.value_escaped_byte_idxs,
+                        &self.variable_syntaxes, self.variable_resolver.as_ref());
+                } else {
+                    self.expand_delimited_value_variables(parse_state);
+                }
+            }
+            parse_state.value_variables_expanded = true;
+        }
+    }
+
+    /// Expands variables in each element of a delimited value list separately so that the delimiter positions can be updated.
+    fn expand_delimited_value_variables(&self, parse_state: &mut ParseState) {
+        let value = &parse_state.value_bldr;
+        let mut expanded = String::with_capacity(value.len());
+        let mut expanded_delimiter_byte_idxs = Vec::with_capacity(parse_state.value_delimiter_byte_idxs.len());
+        let mut element_start_idx = 0;
+        let element_ends = parse_state.value_delimiter_byte_idxs.iter().map(|(idx, delimiter)| (*idx, Some(*delimiter)))
+            .chain(std::iter::once((value.len(), None)));
+        for (element_end_idx, delimiter) in element_ends {
+            let element_escaped_byte_idxs: Vec<usize> = parse_state.value_escaped_byte_idxs.iter()
+                .filter(|idx| (element_start_idx..element_end_idx).contains(*idx))
+                .map(|idx| idx - element_start_idx)
+                .collect();
+            expanded.push_str(&expand_variables(&value[element_start_idx..element_end_idx], &element_escaped_byte_idxs,
+                &self.variable_syntaxes, self.variable_resolver.as_ref()));
+            if let Some(delimiter) = delimiter {
+                expanded_delimiter_byte_idxs.push((expanded.len(), delimiter));
+                expanded.push(delimiter);
+                element_start_idx = element_end_idx + delimiter.len_utf8();
+            }
+        }
+        parse_state.value_bldr = expanded;
+        parse_state.value_delimiter_byte_idxs = expanded_delimiter_byte_idxs;
+    }
+
+    /// Collects the value delimiters of the matchers which can match the option code currently being parsed so that they
+    /// do not need to be looked up for each character of the option's value.
+    fn resolve_option_value_delimiters(&self, parse_state: &mut ParseState) {
+        let mut delimiters = std::mem::take(&mut parse_state.option_value_delimiters);
+        delimiters.clear();
+        for matcher in self.option_code_matchers(&parse_state.option_code) {
+            if let Some(delimiter) = *matcher.value_delimiter() {
+                if !delimiters.contains(&delimiter) && self.try_match_option_excluding_value(parse_state, matcher) {
+                    delimiters.push(delimiter);
+                }
+            }
+        }
+        parse_state.option_value_delimiters = delimiters;
+    }
+
+    /// Whether a character delimits a value list in the value of the option currently being parsed.
+    fn is_option_value_delimiter(&self, parse_state: &ParseState, unicode_char: char) -> bool {
+        parse_state.option_value_delimiters.contains(&unicode_char)
+    }
+
+    /// Checks that an option which takes several value arguments received all of them before another option or the end of the
+    /// arguments.
+    pub(crate) fn finalise_pending_option_values(&self, parse_state: &mut ParseState, args: &Args<'_, O, P>) -> Result<(), ParseError> {
+        let pending_arg_idx = parse_state.pending_values_arg_idx.take();
+        let remaining = parse_state.pending_values_remaining.take();
+        if let (Some(arg_idx), Some(remaining)) = (pending_arg_idx, remaining) {
+            if remaining > 0 {
+                if let Arg::Option(properties) = &args[arg_idx] {
+                    return Err(self.create_option_properties_error(ParseErrorTypeId::OptionMissingValues, properties));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn create_option_properties_error(&self, type_id: ParseErrorTypeId, properties: &OptionProperties<O, P>) -> ParseError {
+        ParseError::new_option(type_id, properties.env_line_approximate_char_index, properties.span, &properties.raw_text,
+            properties.arg_index, properties.option_index, &properties.code, properties.value_text.as_deref().unwrap_or(""))
+    }
+
+    fn add_option_arg<'a>(&self, parse_state: &mut ParseState<'a>, has_value: bool, matcher: &'a Matcher<O, P>, args: &mut Args<'a, O, P>) {
+        let code_matched = self.try_match_option_code(&parse_state.option_code, matcher.option_codes());
+        let negated_by_prefix = !code_matched && self.try_match_negated_option_code(&parse_state.option_code, matcher);
+        let count = if code_matched || negated_by_prefix {
+            1
+        } else {
+            self.try_match_clustered_option_code(&parse_state.option_code, matcher).unwrap_or(1)
+        };
+        let negated_by_announcer = matcher.negating_announcer_chars().contains(&parse_state.option_announcer_char);
+        let enabled = !(negated_by_prefix || negated_by_announcer);
+        let (value_text, value_span, value_raw_text, span, raw_text) = if has_value {
+            let value_span = parse_state.value_span();
+            let value_raw_text = parse_state.raw_text_cow(&value_span);
+            let (span, raw_text) = if parse_state.value_start_env_arg_idx == parse_state.option_env_arg_idx {
+                let span = parse_state.span_from(parse_state.arg_start_char_idx, parse_state.arg_start_byte_idx);
+                (span, parse_state.raw_text_cow(&span))
+            } else {
+                (parse_state.option_code_span, Cow::Owned(parse_state.option_code_raw_text.clone()))
+            };
+            let value_text = parse_state.value_cow(value_span.byte_index);
+            (Some(value_text), Some(value_span), Some(value_raw_text), span, raw_text)
+        } else {
+            let raw_text = parse_state.raw_text_cow(&parse_state.option_code_span);
+            (None, None, None, parse_state.option_code_span, raw_text)
+        };
+        let values = match (&value_text, matcher.value_delimiter()) {
+            (Some(value_text), Some(delimiter)) => parse_state.split_value(*delimiter, value_text),
+            (Some(value_text), None) => vec![value_text.clone()],
+            (None, _) => Vec::new(),
         };
+        if has_value {
+            let remaining = match matcher.option_value_arity() {
+                OptionValueArity::Single => Some(0),
+                OptionValueArity::Exactly(arity) => Some(arity.saturating_sub(1)),
+                OptionValueArity::UntilNextOption => None,
+            };
+            if remaining != Some(0) {
+                parse_state.pending_values_arg_idx = Some(args.len());
+                parse_state.pending_values_remaining = remaining;
+            }
+        }
         let properties = OptionProperties {
             matcher,
             char_index: parse_state.arg_start_char_idx,
             env_line_approximate_char_index: parse_state.arg_start_env_line_approximate_char_idx,
+            span,
+            raw_text,
             arg_index: parse_state.arg_count,
-            env_arg_index: parse_state.env_arg_idx,
+            env_arg_index: parse_state.option_env_arg_idx,
             option_index: parse_state.option_count,
-            code: parse_state.option_code.clone(),
-            value_text
+            code: parse_state.option_code_cow(),
+            code_span: parse_state.option_code_span,
+            value_text,
+            value_span,
+            value_raw_text,
+            values,
+            pairs: Vec::new(),
+            value_source: ValueSource::CommandLine,
+            enabled,
+            announcer_char: parse_state.option_announcer_char,
+            count,
+            captures_case_sensitive: self.option_codes_case_sensitive,
         };
 
         let arg = Arg::Option(properties);
@@ -1204,14 +2304,14 @@ impl<O: Default, P: Default> Parser<O, P> {
         if self.matchers.is_empty() {
             Some(&self.any_matcher)
         } else {
-            self.matchers.iter().find(|&matcher| self.try_match_option(parse_state, has_value, matcher))
+            self.option_code_matchers(&parse_state.option_code).find(|&matcher| self.try_match_option(parse_state, has_value, matcher))
         }
     }
 
     fn try_match_option(&self, parse_state: &ParseState, has_value: bool, matcher: &Matcher<O, P>) -> bool {
         if  self.try_match_option_excluding_value(parse_state, matcher) {
             // want to match value
-            match *matcher.option_has_value() {
+            match *matcher.effective_option_has_value() {
                 OptionHasValue::Always => {
                     // matcher expects value
                     //
@@ -1248,14 +2348,29 @@ impl<O: Default, P: Default> Parser<O, P> {
         }
     }
 
-    fn match_param_arg<'a>(&'a self, parse_state: &mut ParseState, args: &mut Args<'a, O, P>) -> Result<(), ParseError> {
+    fn match_param_arg<'a>(&'a self, parse_state: &mut ParseState<'a>, args: &mut Args<'a, O, P>) -> Result<(), ParseError> {
         if parse_state.current_param_is_binary {
             self.match_binary_arg(parse_state, args)
         } else {
+            self.expand_value_variables(parse_state);
+            if let Some(arg_idx) = parse_state.pending_values_arg_idx {
+                // additional value argument of preceding option
+                if let Arg::Option(properties) = &mut args[arg_idx] {
+                    properties.values.push(parse_state.value_cow(parse_state.arg_start_byte_idx));
+                }
+                if let Some(remaining) = parse_state.pending_values_remaining.as_mut() {
+                    *remaining -= 1;
+                    if *remaining == 0 {
+                        parse_state.pending_values_arg_idx = None;
+                        parse_state.pending_values_remaining = None;
+                    }
+                }
+                return Ok(());
+            }
             let optioned_matcher = if self.matchers.is_empty() {
                 Some(&self.any_matcher)
             } else {
-                self.matchers.iter().find(|&matcher| self.try_match_param(parse_state, matcher))
+                self.param_value_matchers(&parse_state.value_bldr).find(|&matcher| self.try_match_param(parse_state, matcher))
             };
 
             if let Some(matcher) = optioned_matcher {
@@ -1267,15 +2382,20 @@ impl<O: Default, P: Default> Parser<O, P> {
         }
     }
 
-    fn add_param_arg<'a>(&self, parse_state: &mut ParseState, matcher: &'a Matcher<O, P>, args: &mut Args<'a, O, P>) {
+    fn add_param_arg<'a>(&self, parse_state: &mut ParseState<'a>, matcher: &'a Matcher<O, P>, args: &mut Args<'a, O, P>) {
+        let span = parse_state.span_from(parse_state.arg_start_char_idx, parse_state.arg_start_byte_idx);
         let properties = ParamProperties {
             matcher,
             char_index: parse_state.arg_start_char_idx,
             env_line_approximate_char_index: parse_state.arg_start_env_line_approximate_char_idx,
+            span,
+            raw_text: parse_state.raw_text_cow(&span),
             arg_index: parse_state.arg_count,
             env_arg_index: parse_state.env_arg_idx,
             param_index: parse_state.param_count,
-            value_text: parse_state.value_bldr.clone(),
+            value_text: parse_state.value_cow(parse_state.arg_start_byte_idx),
+            value_source: ValueSource::CommandLine,
+            captures_case_sensitive: self.params_case_sensitive,
         };
 
         let arg = Arg::Param(properties);
@@ -1285,19 +2405,22 @@ impl<O: Default, P: Default> Parser<O, P> {
         parse_state.param_count += 1;
     }
 
-    fn match_binary_arg<'a>(&'a self, parse_state: &mut ParseState, args: &mut Args<'a, O, P>) -> Result<(), ParseError> {
+    fn match_binary_arg<'a>(&'a self, parse_state: &mut ParseState<'a>, args: &mut Args<'a, O, P>) -> Result<(), ParseError> {
         self.add_binary_arg(parse_state, &self.any_matcher, args);
         Ok(())
     }
 
-    fn add_binary_arg<'a>(&self, parse_state: &mut ParseState, matcher: &'a Matcher<O, P>, args: &mut Args<'a, O, P>) {
+    fn add_binary_arg<'a>(&self, parse_state: &mut ParseState<'a>, matcher: &'a Matcher<O, P>, args: &mut Args<'a, O, P>) {
+        let span = parse_state.span_from(parse_state.arg_start_char_idx, parse_state.arg_start_byte_idx);
         let properties = BinaryProperties {
             matcher,
             char_index: parse_state.arg_start_char_idx,
             env_line_approximate_char_index: parse_state.arg_start_env_line_approximate_char_idx,
+            span,
+            raw_text: parse_state.raw_text_cow(&span),
             arg_index: parse_state.arg_count,
             env_arg_index: parse_state.env_arg_idx,
-            value_text: parse_state.value_bldr.clone(),
+            value_text: parse_state.value_cow(parse_state.arg_start_byte_idx),
         };
 
         let arg = Arg::Binary(properties);
@@ -1306,6 +2429,22 @@ impl<O: Default, P: Default> Parser<O, P> {
         parse_state.arg_count += 1;
     }
 
+    /// The matchers which may match an option with the specified code, in list order.
+    fn option_code_matchers<'a>(&'a self, code: &str) -> Box<dyn Iterator<Item = &'a Matcher<O, P>> + 'a> {
+        match self.matcher_index.as_ref() {
+            Some(matcher_index) => Box::new(matcher_index.option_matcher_idxs(code).map(|idx| &self.matchers[idx])),
+            None => Box::new(self.matchers.iter()),
+        }
+    }
+
+    /// The matchers which may match a parameter with the specified value, in list order.
+    fn param_value_matchers<'a>(&'a self, value: &str) -> Box<dyn Iterator<Item = &'a Matcher<O, P>> + 'a> {
+        match self.matcher_index.as_ref() {
+            Some(matcher_index) => Box::new(matcher_index.param_matcher_idxs(value).map(|idx| &self.matchers[idx])),
+            None => Box::new(self.matchers.iter()),
+        }
+    }
+
     fn try_match_option_excluding_value(&self, parse_state: &ParseState, matcher: &Matcher<O, P>) -> bool {
         self.try_match_index(&parse_state.arg_count, matcher.arg_indices())
         &&
@@ -1313,7 +2452,49 @@ impl<O: Default, P: Default> Parser<O, P> {
         &&
         self.try_match_index(&parse_state.option_count, matcher.option_indices())
         &&
-        self.try_match_option_code(&parse_state.option_code, matcher.option_codes())
+        (
+            self.try_match_option_code(&parse_state.option_code, matcher.option_codes())
+            ||
+            self.try_match_negated_option_code(&parse_state.option_code, matcher)
+            ||
+            self.try_match_clustered_option_code(&parse_state.option_code, matcher).is_some()
+        )
+    }
+
+    /// If the matcher is [counted](Matcher::counted) and the code is one of the matcher's text codes repeated (eg. `vvv`), returns
+    /// the number of repeats.
+    fn try_match_clustered_option_code(&self, code: &str, matcher: &Matcher<O, P>) -> Option<usize> {
+        if matcher.counted() {
+            let code_chars: Vec<char> = code.chars().collect();
+            for matcher_code in matcher.option_codes_as_slice() {
+                if !matcher_code.is_regex() {
+                    let matcher_code_char_count = matcher_code.text().chars().count();
+                    if matcher_code_char_count > 0 && code_chars.len() > matcher_code_char_count {
+                        let mut repeats = code_chars.chunks_exact(matcher_code_char_count);
+                        if repeats.remainder().is_empty() {
+                            let all_repeats_match = repeats
+                                .all(|chunk| matcher_code.is_match(&chunk.iter().collect::<String>(), self.option_codes_case_sensitive));
+                            if all_repeats_match {
+                                return Some(code_chars.len() / matcher_code_char_count);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns true if the code starts with the matcher's negation prefix and the rest of the code matches the matcher's codes.
+    fn try_match_negated_option_code(&self, code: &str, matcher: &Matcher<O, P>) -> bool {
+        match self.strip_negation_prefix(code, matcher) {
+            Some(stripped_code) => matcher.option_codes().is_some() && self.try_match_option_code(stripped_code, matcher.option_codes()),
+            None => false,
+        }
+    }
+
+    fn strip_negation_prefix<'c>(&self, code: &'c str, matcher: &Matcher<O, P>) -> Option<&'c str> {
+        matcher.strip_negation_prefix(code, self.option_codes_case_sensitive)
     }
 
     fn try_match_param(&self, parse_state: &ParseState, matcher: &Matcher<O, P>) -> bool {
@@ -1364,6 +2545,24 @@ impl<O: Default, P: Default> Parser<O, P> {
     }
 }
 
+/// Parses the fallback value of an option which never has a value. Returns whether the option is enabled or `None` if the
+/// value is not a recognised boolean value.
+fn parse_flag_value(value_text: &str) -> Option<bool> {
+    const ENABLED_VALUES: [&str; 4] = ["true", "yes", "on", "1"];
+    const DISABLED_VALUES: [&str; 4] = ["false", "no", "off", "0"];
+
+    let value_text = value_text.trim();
+    if ENABLED_VALUES.iter().any(|value| value.eq_ignore_ascii_case(value_text)) {
+        Some(true)
+    } else {
+        if DISABLED_VALUES.iter().any(|value| value.eq_ignore_ascii_case(value_text)) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
 /// A logical character is either a group of characters (eg whitespace characters) or a special purpose
 /// character which is configured by the parser (eg Quote character).
 #[derive(Clone)]
@@ -1393,3 +2592,12 @@ enum OptionHasValueBasedOnFirstChar {
     Possibly,
     MustNot,
 }
+
+/// The matcher which matched an argument.
+fn arg_matcher<'a, O: Default, P: Default>(arg: &Arg<'a, O, P>) -> &'a Matcher<O, P> {
+    match arg {
+        Arg::Binary(properties) => properties.matcher,
+        Arg::Param(properties) => properties.matcher,
+        Arg::Option(properties) => properties.matcher,
+    }
+}
@@ -1,6 +1,166 @@
-use regex::{Regex, RegexBuilder};
+use regex::{Captures, Regex, RegexBuilder};
 
-/// Specifies a text string or regex.
+/// Options used when a [RegexOrText] builds its regexes from a pattern. These correspond to the options of the same name in
+/// [RegexBuilder](regex::RegexBuilder).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegexOptions {
+    multi_line: bool,
+    unicode: bool,
+    size_limit: Option<usize>,
+    dfa_size_limit: Option<usize>,
+}
+
+impl Default for RegexOptions {
+    fn default() -> Self {
+        RegexOptions {
+            multi_line: false,
+            unicode: true,
+            size_limit: None,
+            dfa_size_limit: None,
+        }
+    }
+}
+
+impl RegexOptions {
+    /// Create a new `RegexOptions` with the same defaults as [RegexBuilder](regex::RegexBuilder).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `^` and `$` match at the start and end of lines instead of only at the start and end of the text.
+    /// Default: false
+    pub fn multi_line(&self) -> bool {
+        self.multi_line
+    }
+
+    /// Set [multi_line](Self::multi_line).
+    pub fn set_multi_line(&mut self, value: bool) -> &mut Self {
+        self.multi_line = value;
+        self
+    }
+
+    /// Whether Unicode support is enabled. If false, classes like `\w` only match ASCII characters. Default: true
+    pub fn unicode(&self) -> bool {
+        self.unicode
+    }
+
+    /// Set [unicode](Self::unicode).
+    pub fn set_unicode(&mut self, value: bool) -> &mut Self {
+        self.unicode = value;
+        self
+    }
+
+    /// The approximate size limit (in bytes) of a compiled regex. A pattern whose compiled regex exceeds this limit is
+    /// rejected. If `None`, the [RegexBuilder](regex::RegexBuilder) default is used. Default: None
+    pub fn size_limit(&self) -> Option<usize> {
+        self.size_limit
+    }
+
+    /// Set [size_limit](Self::size_limit).
+    pub fn set_size_limit(&mut self, value: Option<usize>) -> &mut Self {
+        self.size_limit = value;
+        self
+    }
+
+    /// Set [size_limit](Self::size_limit) to some value.
+    pub fn some_size_limit(&mut self, value: usize) -> &mut Self {
+        self.size_limit = Some(value);
+        self
+    }
+
+    /// Set [size_limit](Self::size_limit) to `None`.
+    pub fn none_size_limit(&mut self) -> &mut Self {
+        self.size_limit = None;
+        self
+    }
+
+    /// The approximate size limit (in bytes) of the cache used by a regex's lazy DFA. If `None`, the
+    /// [RegexBuilder](regex::RegexBuilder) default is used. Default: None
+    pub fn dfa_size_limit(&self) -> Option<usize> {
+        self.dfa_size_limit
+    }
+
+    /// Set [dfa_size_limit](Self::dfa_size_limit).
+    pub fn set_dfa_size_limit(&mut self, value: Option<usize>) -> &mut Self {
+        self.dfa_size_limit = value;
+        self
+    }
+
+    /// Set [dfa_size_limit](Self::dfa_size_limit) to some value.
+    pub fn some_dfa_size_limit(&mut self, value: usize) -> &mut Self {
+        self.dfa_size_limit = Some(value);
+        self
+    }
+
+    /// Set [dfa_size_limit](Self::dfa_size_limit) to `None`.
+    pub fn none_dfa_size_limit(&mut self) -> &mut Self {
+        self.dfa_size_limit = None;
+        self
+    }
+
+    /// Build a regex from `pattern` with these options.
+    pub(crate) fn build(&self, pattern: &str, case_insensitive: bool) -> Result<Regex, regex::Error> {
+        let mut regex_builder = RegexBuilder::new(pattern);
+        regex_builder
+            .case_insensitive(case_insensitive)
+            .multi_line(self.multi_line)
+            .unicode(self.unicode);
+        if let Some(size_limit) = self.size_limit {
+            regex_builder.size_limit(size_limit);
+        }
+        if let Some(dfa_size_limit) = self.dfa_size_limit {
+            regex_builder.dfa_size_limit(dfa_size_limit);
+        }
+        regex_builder.build()
+    }
+}
+
+/// The capture groups from a regex [RegexOrText] which matched an argument's option code or value.
+///
+/// Group 0 is the whole match. The other groups are numbered in the order of their opening parentheses in the regex.  Groups
+/// which did not participate in the match have no text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegexCaptures {
+    groups: Vec<Option<String>>,
+    names: Vec<Option<String>>,
+}
+
+impl RegexCaptures {
+    fn new(regex: &Regex, captures: &Captures) -> Self {
+        RegexCaptures {
+            groups: captures.iter().map(|group| group.map(|group| String::from(group.as_str()))).collect(),
+            names: regex.capture_names().map(|name| name.map(String::from)).collect(),
+        }
+    }
+
+    /// The number of groups, including group 0.
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Returns true if there are no groups.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// The text of the group at the specified index. `None` if there is no such group or it did not participate in the match.
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.groups.get(index).and_then(|group| group.as_deref())
+    }
+
+    /// The text of the group with the specified name. `None` if there is no such group or it did not participate in the match.
+    pub fn name(&self, name: &str) -> Option<&str> {
+        self.names.iter().position(|group_name| group_name.as_deref() == Some(name)).and_then(|index| self.get(index))
+    }
+
+    /// Iterate over the named groups, returning the name and text of each group in index order.
+    pub fn named(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.names.iter().zip(self.groups.iter())
+            .filter_map(|(name, group)| name.as_deref().map(|name| (name, group.as_deref())))
+    }
+}
+
+/// Specifies a text string, regex or glob pattern.
 /// Used in [Matcher](crate::Matcher) filters to match [parameters](crate::Matcher::value_text),
 /// [option codes](crate::Matcher::option_codes) and [option values](crate::Matcher::value_text).
 /// 
@@ -8,19 +168,31 @@ use regex::{Regex, RegexBuilder};
 /// equal to the text.  If it contains a regex, then the matcher filter will see if the corresponding argument
 /// property is a match for the regex.
 /// 
+/// If it contains a glob pattern (see [with_glob](Self::with_glob)), then the matcher filter will see if the whole of the
+/// corresponding argument property matches the pattern. A glob pattern is matched with a regex built from the pattern, so
+/// [is_regex](Self::is_regex) also returns true for it.
+/// 
 /// Normally, whether or not the text comparison or regex matching is done with case sensitivity, is determined by
 /// the relevant [Parser](crate::Parser) property: [params_case_sensitive](crate::Parser::params_case_sensitive),
 /// [option_codes_case_sensitive](crate::Parser::option_codes_case_sensitive),
 /// [option_values_case_sensitive](crate::Parser::option_values_case_sensitive).  However this can be overridden
 /// with the `RegexOrText`'s [override_case_sensitive](Self::override_case_sensitive) property.
+/// 
+/// Regexes can be built from a pattern with the `try_` functions (eg. [try_with_regex_pattern](Self::try_with_regex_pattern)).
+/// These return an error if the pattern is invalid or exceeds the [size limits](RegexOptions::size_limit) in the
+/// `RegexOrText`'s [regex_options](Self::regex_options), so patterns loaded at runtime (eg. from a configuration file) can be
+/// rejected without panicking.
 #[derive(Debug, Default, Clone)]
 pub struct RegexOrText {
     text: String,
     override_case_sensitive: Option<bool>,
     is_regex: bool,
+    is_glob: bool,
+    regex_built_from_pattern: bool,
     uppercase_text: Option<String>,
     regex: Option<Regex>,
     case_insensitive_regex: Option<Regex>,
+    regex_options: RegexOptions,
 }
 
 impl RegexOrText {
@@ -38,16 +210,63 @@ impl RegexOrText {
     }
 
     /// Create a new `RegexOrText` which matches the specified regex.
+    /// 
+    /// Panics if the case insensitive version of the regex cannot be built. Use [try_with_regex](Self::try_with_regex) to
+    /// get an error instead.
     pub fn with_regex(regex: Regex) -> Self {
+        Self::try_with_regex(regex).expect("Case insensitive regex could not be built")
+    }
+
+    /// Create a new `RegexOrText` which matches the specified regex. Returns an error if the case insensitive version of the
+    /// regex cannot be built.
+    pub fn try_with_regex(regex: Regex) -> Result<Self, regex::Error> {
+        let mut result = RegexOrText::default();
+        result.try_set_regex(regex)?;
+        Ok(result)
+    }
+
+    /// Create a new `RegexOrText` which matches the regex built from `pattern` with the default [RegexOptions].  Returns an
+    /// error if the pattern is not a valid regex.
+    pub fn try_with_regex_pattern(pattern: &str) -> Result<Self, regex::Error> {
+        Self::try_with_regex_pattern_and_options(pattern, RegexOptions::default())
+    }
+
+    /// Create a new `RegexOrText` which matches the regex built from `pattern` with the specified [RegexOptions].  Returns an
+    /// error if the pattern is not a valid regex or the regex exceeds the options' size limits.
+    pub fn try_with_regex_pattern_and_options(pattern: &str, options: RegexOptions) -> Result<Self, regex::Error> {
         let mut result = Self {
-            regex: Some(regex),
-            is_regex: true,
+            regex_options: options,
             ..RegexOrText::default()
         };
+        result.try_set_regex_pattern(pattern)?;
+        Ok(result)
+    }
 
-        result.update();
+    /// Create a new `RegexOrText` which matches the specified glob pattern. The pattern must match the whole of a value and
+    /// can contain:
+    /// * `*` - matches any sequence of characters (including none)
+    /// * `?` - matches any single character
+    /// * `[...]` - matches any one of the characters in the brackets. Ranges (eg. `[a-z]`) are allowed. If the first character
+    ///   in the brackets is `!` or `^`, matches any character not in the brackets.  A `]` immediately after the opening bracket
+    ///   (or negation character) is part of the set.  A range whose end is before its start (eg. `[z-a]`) is not a range: its
+    ///   characters, including the `-`, match themselves.
+    /// 
+    /// All other characters match themselves. Use brackets to match a special character literally (eg. `[*]`).  A `[` without
+    /// a closing `]` also matches itself.
+    /// 
+    /// Every glob pattern is valid, so this only panics if the regex for the pattern exceeds the default size limits. Use
+    /// [try_with_glob](Self::try_with_glob) to get an error instead.
+    pub fn with_glob(pattern: &str) -> Self {
+        Self::try_with_glob(pattern).expect("Glob regex could not be built")
+    }
 
-        result
+    /// Create a new `RegexOrText` which matches the specified [glob pattern](Self::with_glob). Returns an error if the regex for
+    /// the pattern exceeds the default size limits. Malformed brackets and reversed ranges are matched literally and are not
+    /// errors.
+    pub fn try_with_glob(pattern: &str) -> Result<Self, regex::Error> {
+        let mut result = RegexOrText::default();
+        result.try_set_glob(pattern)?;
+        Ok(result)
     }
 
     /// Get the text value of the `RegexOrText`.  Note that this will be ignored if the `RegexOrText` has been configured
@@ -60,6 +279,7 @@ impl RegexOrText {
     pub fn set_text(&mut self, value: &str) -> &mut Self {
         self.text = String::from(value);
         self.is_regex = false;
+        self.is_glob = false;
         self.update();
         self
     }
@@ -69,11 +289,38 @@ impl RegexOrText {
         &self.uppercase_text
     }
 
-    /// Returns true if `RegexOrText` will match with a regex. Otherwise returns false (matches with text).
+    /// Returns true if `RegexOrText` will match with a regex (including a regex built from a glob pattern). Otherwise returns
+    /// false (matches with text).
     pub fn is_regex(&self) -> bool {
         self.regex.is_some()
     }
 
+    /// Returns true if `RegexOrText` will match with a [glob pattern](Self::with_glob). The pattern is held in
+    /// [text](Self::text).
+    pub fn is_glob(&self) -> bool {
+        self.is_glob
+    }
+
+    /// Sets the [glob pattern](Self::with_glob) used for matching. This will also configure the `RegexOrText` to match with the
+    /// glob pattern.  Panics if the regex for the pattern cannot be built. Use [try_set_glob](Self::try_set_glob) to get an
+    /// error instead.
+    pub fn set_glob(&mut self, pattern: &str) -> &mut Self {
+        self.try_set_glob(pattern).expect("Glob regex could not be built")
+    }
+
+    /// Same as [set_glob](Self::set_glob) however returns an error (and leaves the `RegexOrText` unchanged) if the regex for the
+    /// pattern cannot be built with the [regex_options](Self::regex_options).
+    pub fn try_set_glob(&mut self, pattern: &str) -> Result<&mut Self, regex::Error> {
+        self.try_set_built_regexes(pattern, true)
+    }
+
+    /// Whether the regex was built from a pattern (or glob pattern) with the [regex_options](Self::regex_options), rather than
+    /// supplied with [set_regex](Self::set_regex).  A supplied regex may have been built with flags which its pattern does not
+    /// reproduce.
+    pub(crate) fn is_regex_built_from_pattern(&self) -> bool {
+        self.regex_built_from_pattern
+    }
+
     /// Get the optioned regex value of the `RegexOrText`.  Note that this will be ignored if the `RegexOrText` has been configured
     /// to match with text ([is_regex](Self::is_regex) returns false).
     pub fn regex(&self) -> &Option<Regex> {
@@ -81,11 +328,58 @@ impl RegexOrText {
     }
 
     /// Sets the regex used for matching. This will also configure the `RegexOrText` to match with regex.
+    /// 
+    /// The regex is used as is for case sensitive matching. The regex used for case insensitive matching is built from its
+    /// pattern with the [regex_options](Self::regex_options). Panics if this cannot be built. Use
+    /// [try_set_regex](Self::try_set_regex) to get an error instead.
     pub fn set_regex(&mut self, value: Regex) -> &mut Self {
+        self.try_set_regex(value).expect("Case insensitive regex could not be built")
+    }
+
+    /// Same as [set_regex](Self::set_regex) however returns an error (and leaves the `RegexOrText` unchanged) if the case
+    /// insensitive version of the regex cannot be built.
+    pub fn try_set_regex(&mut self, value: Regex) -> Result<&mut Self, regex::Error> {
+        let case_insensitive_regex = self.regex_options.build(value.as_str(), true)?;
         self.text = String::from(value.as_str());
         self.is_regex = true;
-        self.update();
-        self
+        self.is_glob = false;
+        self.regex_built_from_pattern = false;
+        self.uppercase_text = None;
+        self.regex = Some(value);
+        self.case_insensitive_regex = Some(case_insensitive_regex);
+        Ok(self)
+    }
+
+    /// Sets the regex used for matching to one built from `pattern` with the [regex_options](Self::regex_options). This will also
+    /// configure the `RegexOrText` to match with regex.  Returns an error (and leaves the `RegexOrText` unchanged) if the pattern
+    /// is not a valid regex or the regex exceeds the options' size limits.
+    pub fn try_set_regex_pattern(&mut self, pattern: &str) -> Result<&mut Self, regex::Error> {
+        self.try_set_built_regexes(pattern, false)
+    }
+
+    /// The options used when building regexes from a pattern.
+    pub fn regex_options(&self) -> &RegexOptions {
+        &self.regex_options
+    }
+
+    /// Set [regex_options](Self::regex_options).  If the `RegexOrText` matches with regex or a glob pattern, its regexes are
+    /// rebuilt from the pattern with the new options. Returns an error (and leaves the `RegexOrText` unchanged) if they cannot
+    /// be built.
+    pub fn try_set_regex_options(&mut self, value: RegexOptions) -> Result<&mut Self, regex::Error> {
+        if self.is_regex {
+            let pattern = if self.is_glob {
+                glob_to_regex_pattern(&self.text)
+            } else {
+                self.text.clone()
+            };
+            let regex = value.build(&pattern, false)?;
+            let case_insensitive_regex = value.build(&pattern, true)?;
+            self.regex = Some(regex);
+            self.case_insensitive_regex = Some(case_insensitive_regex);
+            self.regex_built_from_pattern = true;
+        }
+        self.regex_options = value;
+        Ok(self)
     }
 
     /// Gets an optioned boolean which indicates whether matching will be case sensitive.  If the value is `None` then matching
@@ -129,26 +423,107 @@ impl RegexOrText {
         }
     }
 
-    fn update(&mut self) {
-        if self.is_regex {
-            self.uppercase_text = None;
+    /// If `RegexOrText` matches with a regex (not a glob pattern) and the regex matches value, returns the regex's capture groups.
+    /// Otherwise returns `None`. The `case_sensitive` parameter is used the same as in [is_match](Self::is_match).
+    pub fn captures(&self, value: &str, mut case_sensitive: bool) -> Option<RegexCaptures> {
+        if self.is_glob {
+            return None;
+        }
 
-            self.regex = Some(Regex::new(&self.text).unwrap());
+        if let Some(override_case_sensitive) = self.override_case_sensitive {
+            case_sensitive = override_case_sensitive;
+        }
 
-            let mut regex_builder = RegexBuilder::new(&self.text);
-            regex_builder.case_insensitive(true);
-            self.case_insensitive_regex = Some(regex_builder.build().unwrap());
+        let optioned_regex_ref = if case_sensitive {
+            self.regex.as_ref()
         } else {
-            self.regex = None;
-            self.case_insensitive_regex = None;
+            self.case_insensitive_regex.as_ref()
+        };
 
-            let mut uppercase_text = String::with_capacity(self.text.len());
+        optioned_regex_ref.and_then(|regex| regex.captures(value).map(|captures| RegexCaptures::new(regex, &captures)))
+    }
 
-            for char in self.text.chars() {
-                uppercase_text.push(char);
-            }
-            self.uppercase_text = Some(self.text.to_uppercase());
+    /// Builds the regexes from a regex or glob pattern with the regex options and, if successful, configures the
+    /// `RegexOrText` to match with them.
+    fn try_set_built_regexes(&mut self, pattern: &str, is_glob: bool) -> Result<&mut Self, regex::Error> {
+        let regex_pattern = if is_glob {
+            glob_to_regex_pattern(pattern)
+        } else {
+            String::from(pattern)
+        };
+        let regex = self.regex_options.build(&regex_pattern, false)?;
+        let case_insensitive_regex = self.regex_options.build(&regex_pattern, true)?;
+        self.text = String::from(pattern);
+        self.is_regex = true;
+        self.is_glob = is_glob;
+        self.regex_built_from_pattern = true;
+        self.uppercase_text = None;
+        self.regex = Some(regex);
+        self.case_insensitive_regex = Some(case_insensitive_regex);
+        Ok(self)
+    }
+
+    fn update(&mut self) {
+        self.regex = None;
+        self.case_insensitive_regex = None;
+        self.uppercase_text = Some(self.text.to_uppercase());
+    }
+}
+
+/// Converts a glob pattern into an anchored regex pattern.
+fn glob_to_regex_pattern(glob: &str) -> String {
+    let chars: Vec<char> = glob.chars().collect();
+    let mut pattern = String::from("(?s)^(?:");
+    let mut idx = 0;
+    while idx < chars.len() {
+        match chars[idx] {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            '[' => match glob_bracket_end_idx(&chars, idx) {
+                Some(end_idx) => {
+                    push_glob_bracket_class(&mut pattern, &chars[idx + 1..end_idx]);
+                    idx = end_idx;
+                }
+                None => pattern.push_str(&regex::escape("[")),
+            },
+            char => pattern.push_str(&regex::escape(char.encode_utf8(&mut [0; 4]))),
+        }
+        idx += 1;
+    }
+    pattern.push_str(")$");
+    pattern
+}
+
+/// The index of the `]` which closes the bracket expression starting at `start_idx`, if any.
+fn glob_bracket_end_idx(chars: &[char], start_idx: usize) -> Option<usize> {
+    let mut idx = start_idx + 1;
+    if idx < chars.len() && (chars[idx] == '!' || chars[idx] == '^') {
+        idx += 1;
+    }
+    // a leading ']' is part of the set
+    if idx < chars.len() && chars[idx] == ']' {
+        idx += 1;
+    }
+    chars[idx.min(chars.len())..].iter().position(|&char| char == ']').map(|position| idx + position)
+}
+
+fn push_glob_bracket_class(pattern: &mut String, contents: &[char]) {
+    let (negated, set) = match contents.first() {
+        Some('!') | Some('^') => (true, &contents[1..]),
+        _ => (false, contents),
+    };
+    pattern.push('[');
+    if negated {
+        pattern.push('^');
+    }
+    for (idx, &char) in set.iter().enumerate() {
+        if char == '-' && idx > 0 && idx + 1 < set.len() && set[idx - 1] <= set[idx + 1] {
+            // range (a reversed range is not a range so its '-' is escaped below)
+            pattern.push('-');
+        } else {
+            pattern.push_str(&regex::escape(char.encode_utf8(&mut [0; 4])));
         }
     }
+    pattern.push(']');
 }
 
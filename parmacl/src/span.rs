@@ -0,0 +1,42 @@
+/// The position of some text within a command line (or environment argument). Positions are available both as character
+/// indices and byte indices. The byte indices can be used to slice the original `&str`.
+///
+/// The end indices are exclusive. That is, they are the indices of the character (byte) following the text.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// Index of the first character of the text.
+    pub char_index: usize,
+    /// Index of the character after the last character of the text.
+    pub end_char_index: usize,
+    /// Index of the first byte of the text.
+    pub byte_index: usize,
+    /// Index of the byte after the last byte of the text.
+    pub end_byte_index: usize,
+}
+
+impl Span {
+    /// Create a new `Span` from start and end character and byte indices.
+    pub fn new(char_index: usize, end_char_index: usize, byte_index: usize, end_byte_index: usize) -> Self {
+        Span {
+            char_index,
+            end_char_index,
+            byte_index,
+            end_byte_index,
+        }
+    }
+
+    /// Number of characters in the text.
+    pub fn char_len(&self) -> usize {
+        self.end_char_index - self.char_index
+    }
+
+    /// Number of bytes in the text.
+    pub fn byte_len(&self) -> usize {
+        self.end_byte_index - self.byte_index
+    }
+
+    /// Returns true if the span does not contain any characters.
+    pub fn is_empty(&self) -> bool {
+        self.end_char_index == self.char_index
+    }
+}
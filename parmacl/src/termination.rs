@@ -0,0 +1,27 @@
+use crate::span::Span;
+
+/// Details of where parsing was terminated by one of the [parse terminate characters](crate::Parser::parse_terminate_chars).
+///
+/// Returned by [Parser.parse_line_with_termination](crate::Parser::parse_line_with_termination) and
+/// [Parser.parse_env_args_with_termination](crate::Parser::parse_env_args_with_termination). This allows an application to use
+/// the text after the terminate character. For example, to chain commands with `;` or `|`, or to process a `#` comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Termination {
+    /// The parse terminate character which terminated parsing.
+    pub terminate_char: char,
+    /// Position of the terminate character. If a line was parsed, this will be the position in the line. If environmental
+    /// arguments were parsed, this will be the position in the environmental argument containing the terminate character.
+    pub span: Span,
+    /// Approximate index of the terminate character in the line. If a line was parsed, this will be the same as the
+    /// [span's](Self::span) `char_index` and be exact.
+    pub env_line_approximate_char_index: usize,
+    /// Index of the environment argument containing the terminate character.  If a line is parsed, this will always be zero.
+    pub env_arg_index: usize,
+    /// The unparsed text following the terminate character, up to the end of the line (or environmental argument).
+    pub remainder: String,
+    /// Position of [remainder](Self::remainder).
+    pub remainder_span: Span,
+    /// If environmental arguments were parsed, the environmental arguments following the one containing the terminate
+    /// character. These were not parsed. If a line was parsed, this will always be empty.
+    pub remaining_env_args: Vec<String>,
+}
@@ -0,0 +1,372 @@
+use std::iter::Peekable;
+use std::str::CharIndices;
+use crate::parse_error_type_id::{ParseErrorTypeId};
+use crate::parse_error::{ParseError};
+use crate::matcher::{DefaultTagType};
+use crate::parser::{Parser};
+use crate::parse_state::{extract_option_code};
+use crate::span::{Span};
+
+/// The kind of a [Token] returned by a [Tokenizer].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// The first argument in the line if the parser's [first_arg_is_binary](crate::Parser::first_arg_is_binary) is true.
+    Binary,
+    /// The code of an option. The token's span includes the option announcer character(s).
+    OptionCode,
+    /// An option value announced with a non-whitespace [option value announcer](crate::Parser::option_value_announcer_chars).
+    OptionValue,
+    /// A parameter. Note that a word following an option code separated by whitespace, is always returned as a parameter.
+    /// Whether such a word is an option value can only be determined by matchers.
+    Param,
+    /// Whitespace between arguments or a non-whitespace option value announcer character.
+    Separator,
+    /// A [parse terminate character](crate::Parser::parse_terminate_chars) together with the rest of the line.
+    Terminator,
+}
+
+/// A lexical element of a command line returned by a [Tokenizer].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// The kind of token.
+    pub kind: TokenKind,
+    /// For [OptionCode](TokenKind::OptionCode) tokens, this is the option code (excluding announcer characters).  For
+    /// [Binary](TokenKind::Binary), [Param](TokenKind::Param) and [OptionValue](TokenKind::OptionValue) tokens, this is the
+    /// value with quotes and escape characters removed. For other tokens, it is the text as it appears in the line.
+    pub text: String,
+    /// The position of the token in the line.
+    pub span: Span,
+    /// If the value was quoted, this holds the quote character. Otherwise it is `None`.
+    pub quote_char: Option<char>,
+}
+
+impl Token {
+    /// Returns true if the token is a quoted value.
+    pub fn is_quoted(&self) -> bool {
+        self.quote_char.is_some()
+    }
+}
+
+/// An iterator which splits a command line into [tokens](Token) without matching arguments against matchers.
+///
+/// Created with [Parser.tokenize](crate::Parser::tokenize). The line is split according to the style properties of the parser
+/// (quote characters, option announcers, escaping etc.) in the same way as the parser splits a line into arguments. This is
+/// useful where only the lexical structure of a line is needed, for example, syntax highlighting.
+///
+/// Matchers are not used when tokenizing. A word separated from an option code by whitespace is therefore always returned
+/// as a [Param](TokenKind::Param) token, even if the parser would use it as the option's value. In this case, a parse
+/// terminate character at the start of the word is returned as a [Terminator](TokenKind::Terminator) and errors in the word
+/// are returned as parameter errors.
+///
+/// Each item is a result. If a lexical error is encountered (for example, a missing closing quote), an error result is returned
+/// and iteration finishes.
+pub struct Tokenizer<'a, O: Default = DefaultTagType, P: Default = DefaultTagType> {
+    parser: &'a Parser<O, P>,
+    line: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    char_idx: usize,
+    wait_binary: bool,
+    value_announcer_pending: bool,
+    wait_option_value: bool,
+    finished: bool,
+    arg_count: usize,
+    option_count: usize,
+    param_count: usize,
+    option_code: String,
+}
+
+impl<'a, O: Default, P: Default> Tokenizer<'a, O, P> {
+    /// Create a new Tokenizer which splits `line` according to the style of `parser`.
+    pub fn new(parser: &'a Parser<O, P>, line: &'a str) -> Self {
+        Tokenizer {
+            parser,
+            line,
+            chars: line.char_indices().peekable(),
+            char_idx: 0,
+            wait_binary: parser.first_arg_is_binary(),
+            value_announcer_pending: false,
+            wait_option_value: false,
+            finished: false,
+            arg_count: 0,
+            option_count: 0,
+            param_count: 0,
+            option_code: String::from(""),
+        }
+    }
+
+    fn byte_idx(&mut self) -> usize {
+        match self.chars.peek() {
+            Some((byte_idx, _)) => *byte_idx,
+            None => self.line.len(),
+        }
+    }
+
+    fn peek_char(&mut self) -> Option<char> {
+        self.chars.peek().map(|(_, char)| *char)
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let optioned_char = self.chars.next().map(|(_, char)| char);
+        if optioned_char.is_some() {
+            self.char_idx += 1;
+        }
+        optioned_char
+    }
+
+    fn create_token(&mut self, kind: TokenKind, text: String, start_char_idx: usize, start_byte_idx: usize, quote_char: Option<char>) -> Token {
+        let span = Span::new(start_char_idx, self.char_idx, start_byte_idx, self.byte_idx());
+        Token {
+            kind,
+            text,
+            span,
+            quote_char,
+        }
+    }
+
+    fn take_separator(&mut self) -> Token {
+        let start_char_idx = self.char_idx;
+        let start_byte_idx = self.byte_idx();
+        if self.value_announcer_pending {
+            self.next_char();
+            self.value_announcer_pending = false;
+            self.wait_option_value = true;
+        } else {
+            while let Some(char) = self.peek_char() {
+                if char.is_whitespace() {
+                    self.next_char();
+                } else {
+                    break;
+                }
+            }
+        }
+        let end_byte_idx = self.byte_idx();
+        let text = String::from(&self.line[start_byte_idx..end_byte_idx]);
+        self.create_token(TokenKind::Separator, text, start_char_idx, start_byte_idx, None)
+    }
+
+    fn take_terminator(&mut self) -> Token {
+        let start_char_idx = self.char_idx;
+        let start_byte_idx = self.byte_idx();
+        while self.next_char().is_some() {}
+        self.finished = true;
+        let text = String::from(&self.line[start_byte_idx..]);
+        self.create_token(TokenKind::Terminator, text, start_char_idx, start_byte_idx, None)
+    }
+
+    fn take_option_code(&mut self, option_announcer_char: char) -> Result<Token, ParseError> {
+        let start_char_idx = self.char_idx;
+        let start_byte_idx = self.byte_idx();
+        self.next_char();
+        let code_start_byte_idx = self.byte_idx();
+
+        while let Some(char) = self.peek_char() {
+            if char.is_whitespace() || self.parser.parse_terminate_chars().contains(&char) {
+                break;
+            } else {
+                if self.parser.option_value_announcer_chars().contains(&char) {
+                    self.value_announcer_pending = true;
+                    break;
+                } else {
+                    if self.parser.quote_chars().contains(&char) {
+                        return Err(self.create_option_error(ParseErrorTypeId::OptionCodeCannotContainQuoteChar, start_char_idx, start_byte_idx, ""));
+                    } else {
+                        if Some(char) == *self.parser.escape_char() {
+                            return Err(self.create_option_error(ParseErrorTypeId::OptionCodeCannotContainEscapeChar, start_char_idx, start_byte_idx, ""));
+                        } else {
+                            self.next_char();
+                            self.option_code.push(char);
+                        }
+                    }
+                }
+            }
+        }
+
+        let end_byte_idx = self.byte_idx();
+        let raw_option_code = &self.line[code_start_byte_idx..end_byte_idx];
+        let multi_char_option_code_requires_double_announcer = self.parser.multi_char_option_code_requires_double_announcer();
+        match extract_option_code(raw_option_code, option_announcer_char, multi_char_option_code_requires_double_announcer) {
+            Ok(option_code) => {
+                let text = String::from(option_code);
+                if !self.value_announcer_pending {
+                    self.finish_option();
+                }
+                Ok(self.create_token(TokenKind::OptionCode, text, start_char_idx, start_byte_idx, None))
+            }
+            Err(error_id) => {
+                Err(self.create_option_error(error_id, start_char_idx, start_byte_idx, ""))
+            }
+        }
+    }
+
+    fn take_value(&mut self, kind: TokenKind, first_char: char) -> Result<Token, ParseError> {
+        let start_char_idx = self.char_idx;
+        let start_byte_idx = self.byte_idx();
+        let mut value_bldr = String::with_capacity(30);
+
+        let quote_char = if self.parser.quote_chars().contains(&first_char) {
+            self.next_char();
+            Some(first_char)
+        } else {
+            None
+        };
+
+        loop {
+            match self.peek_char() {
+                None => {
+                    if quote_char.is_some() {
+                        let error_id = if kind == TokenKind::OptionValue {
+                            ParseErrorTypeId::OptionValueMissingClosingQuoteCharacter
+                        } else {
+                            ParseErrorTypeId::ParamMissingClosingQuoteCharacter
+                        };
+                        return Err(self.create_value_error(kind, error_id, start_char_idx, start_byte_idx, &value_bldr));
+                    } else {
+                        break;
+                    }
+                }
+                Some(char) => {
+                    if Some(char) == *self.parser.escape_char() {
+                        self.next_char();
+                        match self.peek_char() {
+                            None => {
+                                // same errors as the parser returns when a line ends with an escape character
+                                let error_id = if kind == TokenKind::OptionValue {
+                                    ParseErrorTypeId::EscapeCharacterAtEndOfLine
+                                } else {
+                                    ParseErrorTypeId::EscapedCharacterInParamCannotBeEscaped
+                                };
+                                return Err(self.create_value_error(kind, error_id, start_char_idx, start_byte_idx, &value_bldr));
+                            }
+                            Some(escaped_char) => {
+                                if self.parser.can_char_be_escaped(escaped_char, quote_char) {
+                                    self.next_char();
+                                    value_bldr.push(escaped_char);
+                                } else {
+                                    let error_id = if kind == TokenKind::OptionValue {
+                                        ParseErrorTypeId::EscapedCharacterInOptionValueCannotBeEscaped
+                                    } else {
+                                        ParseErrorTypeId::EscapedCharacterInParamCannotBeEscaped
+                                    };
+                                    return Err(self.create_value_error(kind, error_id, start_char_idx, start_byte_idx, &value_bldr));
+                                }
+                            }
+                        }
+                    } else {
+                        if let Some(unwrapped_quote_char) = quote_char {
+                            self.next_char();
+                            if char == unwrapped_quote_char {
+                                if self.parser.embed_quote_char_with_double() && self.peek_char() == Some(char) {
+                                    self.next_char();
+                                    value_bldr.push(char);
+                                } else {
+                                    if let Some(next_char) = self.peek_char() {
+                                        if !next_char.is_whitespace() {
+                                            let error_id = if kind == TokenKind::OptionValue {
+                                                ParseErrorTypeId::QuotedOptionValueNotFollowedByWhitespaceChar
+                                            } else {
+                                                ParseErrorTypeId::QuotedParamNotFollowedByWhitespaceChar
+                                            };
+                                            return Err(self.create_value_error(kind, error_id, start_char_idx, start_byte_idx, &value_bldr));
+                                        }
+                                    }
+                                    break;
+                                }
+                            } else {
+                                value_bldr.push(char);
+                            }
+                        } else {
+                            if char.is_whitespace() {
+                                break;
+                            } else {
+                                self.next_char();
+                                value_bldr.push(char);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        match kind {
+            TokenKind::OptionValue => self.finish_option(),
+            _ => {
+                if kind == TokenKind::Param {
+                    self.param_count += 1;
+                }
+                self.arg_count += 1;
+            }
+        }
+
+        Ok(self.create_token(kind, value_bldr, start_char_idx, start_byte_idx, quote_char))
+    }
+
+    fn finish_option(&mut self) {
+        self.option_code.clear();
+        self.arg_count += 1;
+        self.option_count += 1;
+    }
+
+    fn create_value_error(&mut self, kind: TokenKind, error_id: ParseErrorTypeId, start_char_idx: usize, start_byte_idx: usize, value_text: &str)
+        -> ParseError
+    {
+        if kind == TokenKind::OptionValue {
+            self.create_option_error(error_id, start_char_idx, start_byte_idx, value_text)
+        } else {
+            let span = Span::new(start_char_idx, self.char_idx, start_byte_idx, self.byte_idx());
+            ParseError::new_param(error_id, self.char_idx, span, &self.line[start_byte_idx..self.byte_idx()], self.arg_count, self.param_count, value_text)
+        }
+    }
+
+    fn create_option_error(&mut self, error_id: ParseErrorTypeId, start_char_idx: usize, start_byte_idx: usize, value_text: &str) -> ParseError {
+        let span = Span::new(start_char_idx, self.char_idx, start_byte_idx, self.byte_idx());
+        ParseError::new_option(error_id, self.char_idx, span, &self.line[start_byte_idx..self.byte_idx()], self.arg_count, self.option_count, &self.option_code, value_text)
+    }
+}
+
+impl<'a, O: Default, P: Default> Iterator for Tokenizer<'a, O, P> {
+    type Item = Result<Token, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let first_char = match self.peek_char() {
+            None => {
+                self.finished = true;
+                return None;
+            }
+            Some(first_char) => first_char,
+        };
+
+        let result = if first_char.is_whitespace() || self.value_announcer_pending {
+            Ok(self.take_separator())
+        } else {
+            if self.wait_option_value {
+                self.wait_option_value = false;
+                self.take_value(TokenKind::OptionValue, first_char)
+            } else {
+                if self.parser.parse_terminate_chars().contains(&first_char) {
+                    Ok(self.take_terminator())
+                } else {
+                    if self.wait_binary {
+                        self.wait_binary = false;
+                        self.take_value(TokenKind::Binary, first_char)
+                    } else {
+                        if self.parser.option_announcer_chars().contains(&first_char) {
+                            self.take_option_code(first_char)
+                        } else {
+                            self.take_value(TokenKind::Param, first_char)
+                        }
+                    }
+                }
+            }
+        };
+
+        if result.is_err() {
+            self.finished = true;
+        }
+
+        Some(result)
+    }
+}
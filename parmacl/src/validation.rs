@@ -0,0 +1,170 @@
+use std::fmt::Display;
+use crate::matcher::{Matcher, MatchArgTypeId, OptionHasValue};
+use crate::parser::{Parser};
+use crate::regex_or_text::{RegexOrText};
+
+/// How serious a [ValidationIssue] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationSeverity {
+    /// The matchers will work but probably not as intended.
+    Warning,
+    /// The matchers are misconfigured.
+    Error,
+}
+
+/// The types of issues which can be returned by [Parser.validate](crate::Parser::validate).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationIssueTypeId {
+    /// A matcher whose [arg_type](crate::Matcher::arg_type) is [Param](MatchArgTypeId::Param) has
+    /// [option_codes](crate::Matcher::option_codes). The option codes are ignored.
+    ParamMatcherHasOptionCodes,
+    /// More than one matcher has the same [name](crate::Matcher::name).  [Parser.find_matcher](crate::Parser::find_matcher)
+    /// only finds the first of these matchers.
+    DuplicateMatcherName,
+    /// Every option the matcher can match is matched by an earlier matcher with the same text option code, so this matcher
+    /// can never match.
+    UnreachableOptionMatcher,
+    /// A matcher's [option_has_value](crate::Matcher::option_has_value) is [IfPossible](OptionHasValue::IfPossible) however
+    /// none of the parser's [option value announcer characters](crate::Parser::option_value_announcer_chars) are whitespace.
+    /// An option value is then never ambiguous so [IfPossible](OptionHasValue::IfPossible) is not needed to resolve it.
+    IfPossibleWithoutWhitespaceValueAnnouncer,
+}
+
+impl ValidationIssueTypeId {
+    /// Get the default (English) text which describes an issue type.
+    pub fn get_default_text(&self) -> &str {
+        match self {
+            ValidationIssueTypeId::ParamMatcherHasOptionCodes => "Param matcher has option codes",
+            ValidationIssueTypeId::DuplicateMatcherName => "Duplicate matcher name",
+            ValidationIssueTypeId::UnreachableOptionMatcher => "Option matcher is unreachable",
+            ValidationIssueTypeId::IfPossibleWithoutWhitespaceValueAnnouncer => "IfPossible used without whitespace value announcer",
+        }
+    }
+
+    /// The severity of this type of issue.
+    pub fn severity(&self) -> ValidationSeverity {
+        match self {
+            ValidationIssueTypeId::ParamMatcherHasOptionCodes => ValidationSeverity::Error,
+            ValidationIssueTypeId::DuplicateMatcherName => ValidationSeverity::Error,
+            ValidationIssueTypeId::UnreachableOptionMatcher => ValidationSeverity::Warning,
+            ValidationIssueTypeId::IfPossibleWithoutWhitespaceValueAnnouncer => ValidationSeverity::Warning,
+        }
+    }
+}
+
+impl Display for ValidationIssueTypeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.get_default_text())
+    }
+}
+
+/// An issue with a [Parser]'s matchers found by [Parser.validate](crate::Parser::validate).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// The type of issue.
+    pub type_id: ValidationIssueTypeId,
+    /// The severity of the issue.
+    pub severity: ValidationSeverity,
+    /// The position of the matcher with the issue in the parser's [matchers](crate::Parser::matchers).
+    pub matcher_index: usize,
+    /// The name of the matcher with the issue.
+    pub matcher_name: String,
+    /// For [DuplicateMatcherName](ValidationIssueTypeId::DuplicateMatcherName) and
+    /// [UnreachableOptionMatcher](ValidationIssueTypeId::UnreachableOptionMatcher) issues, the position of the earlier
+    /// matcher in the parser's [matchers](crate::Parser::matchers). Otherwise `None`.
+    pub other_matcher_index: Option<usize>,
+}
+
+impl ValidationIssue {
+    fn new(type_id: ValidationIssueTypeId, matcher_index: usize, matcher_name: &str, other_matcher_index: Option<usize>) -> Self {
+        ValidationIssue {
+            type_id,
+            severity: type_id.severity(),
+            matcher_index,
+            matcher_name: String::from(matcher_name),
+            other_matcher_index,
+        }
+    }
+}
+
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity_text = match self.severity {
+            ValidationSeverity::Warning => "warning",
+            ValidationSeverity::Error => "error",
+        };
+        write!(f, "{}: {} [m:{} \"{}\"", severity_text, self.type_id.get_default_text(), self.matcher_index, self.matcher_name)?;
+        if let Some(other_matcher_index) = self.other_matcher_index {
+            write!(f, " other:{}", other_matcher_index)?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// Checks the parser's matchers for coherence. See [Parser.validate](crate::Parser::validate).
+pub(crate) fn validate_parser<O: Default, P: Default>(parser: &Parser<O, P>) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let whitespace_value_announcer = parser.option_value_announcer_chars().iter().any(|announcer_char| announcer_char.is_whitespace());
+
+    let matchers = parser.matchers();
+    for (matcher_idx, matcher) in matchers.iter().enumerate() {
+        let earlier_matchers = &matchers[..matcher_idx];
+
+        if *matcher.arg_type() == Some(MatchArgTypeId::Param) && matcher.option_codes().is_some() {
+            issues.push(ValidationIssue::new(ValidationIssueTypeId::ParamMatcherHasOptionCodes, matcher_idx, matcher.name(), None));
+        }
+
+        if let Some(earlier_idx) = earlier_matchers.iter().position(|earlier_matcher| earlier_matcher.name() == matcher.name()) {
+            issues.push(ValidationIssue::new(ValidationIssueTypeId::DuplicateMatcherName, matcher_idx, matcher.name(), Some(earlier_idx)));
+        }
+
+        if *matcher.arg_type() != Some(MatchArgTypeId::Param) {
+            let covering_idx = earlier_matchers.iter().position(|earlier_matcher|
+                covers_option_matcher(earlier_matcher, matcher, parser.option_codes_case_sensitive())
+            );
+            if let Some(earlier_idx) = covering_idx {
+                issues.push(ValidationIssue::new(ValidationIssueTypeId::UnreachableOptionMatcher, matcher_idx, matcher.name(), Some(earlier_idx)));
+            }
+
+            if *matcher.effective_option_has_value() == OptionHasValue::IfPossible && !whitespace_value_announcer {
+                issues.push(ValidationIssue::new(ValidationIssueTypeId::IfPossibleWithoutWhitespaceValueAnnouncer, matcher_idx, matcher.name(),
+                    None));
+            }
+        }
+    }
+
+    issues
+}
+
+/// Returns true if `earlier` matches every option which `later` can match. Only text option codes are compared so this may
+/// miss some unreachable matchers but does not report reachable ones.
+fn covers_option_matcher<O: Default, P: Default>(earlier: &Matcher<O, P>, later: &Matcher<O, P>, case_sensitive: bool) -> bool {
+    let earlier_unrestricted = *earlier.arg_type() != Some(MatchArgTypeId::Param)
+        && earlier.arg_indices().is_none()
+        && earlier.option_indices().is_none()
+        && earlier.value_text().is_none();
+    // negated and clustered codes can match options which the earlier matcher does not
+    let later_plain = later.negation_prefix().is_none() && !later.counted();
+    let value_covered = *earlier.effective_option_has_value() == OptionHasValue::IfPossible
+        || earlier.effective_option_has_value() == later.effective_option_has_value();
+
+    earlier_unrestricted && later_plain && value_covered && !later.option_codes_as_slice().is_empty()
+        && later.option_codes_as_slice().iter().all(|later_code|
+            earlier.option_codes_as_slice().iter().any(|earlier_code| covers_option_code(earlier_code, later_code, case_sensitive))
+        )
+}
+
+fn covers_option_code(earlier_code: &RegexOrText, later_code: &RegexOrText, case_sensitive: bool) -> bool {
+    if earlier_code.is_regex() || later_code.is_regex() {
+        false
+    } else {
+        let earlier_case_sensitive = earlier_code.override_case_sensitive().unwrap_or(case_sensitive);
+        let later_case_sensitive = later_code.override_case_sensitive().unwrap_or(case_sensitive);
+        if earlier_case_sensitive {
+            // a case insensitive later code also matches other cases
+            later_case_sensitive && earlier_code.text() == later_code.text()
+        } else {
+            earlier_code.text().to_uppercase() == later_code.text().to_uppercase()
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::env;
+
+/// The syntaxes which can be used to reference a variable in a parameter or option value. See
+/// [Parser.variable_syntaxes](crate::Parser::variable_syntaxes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableSyntax {
+    /// `$NAME` where name starts with a letter or underscore followed by letters, digits or underscores.
+    Dollar,
+    /// `${NAME}`
+    DollarBraces,
+    /// `${NAME:-default}`. The default is used if the variable cannot be resolved or its value is empty. Also supports
+    /// `${NAME}`.
+    DollarBracesDefault,
+    /// `%NAME%` where name does not contain whitespace.
+    Percent,
+}
+
+/// Supplies the values of variables referenced in parameters and option values
+/// ([Parser.variable_resolver](crate::Parser::variable_resolver)) and of matchers' environment variables
+/// ([Parser.env_var_resolver](crate::Parser::env_var_resolver)).
+///
+/// This is implemented for [EnvVariableResolver] (the default resolver), `HashMap<String, String>` and closures with
+/// signature `Fn(&str) -> Option<String>`. Resolvers must be `Send` and `Sync` so that a [Parser](crate::Parser) can be shared
+/// between threads.
+pub trait VariableResolver: Send + Sync {
+    /// Returns the value of the variable with the specified name or `None` if the variable is not defined.
+    fn resolve(&self, name: &str) -> Option<String>;
+}
+
+/// A [VariableResolver] which resolves variables from the process's environment variables.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EnvVariableResolver;
+
+impl VariableResolver for EnvVariableResolver {
+    fn resolve(&self, name: &str) -> Option<String> {
+        env::var(name).ok()
+    }
+}
+
+impl VariableResolver for HashMap<String, String> {
+    fn resolve(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+}
+
+impl<F: Fn(&str) -> Option<String> + Send + Sync> VariableResolver for F {
+    fn resolve(&self, name: &str) -> Option<String> {
+        self(name)
+    }
+}
+
+/// Replaces variable references in `text` with their resolved values. Characters at the byte indices in `escaped_byte_idxs`
+/// were escaped and cannot start a variable reference.  References to variables which cannot be resolved (and do not have a
+/// default) are left unchanged.
+pub(crate) fn expand_variables(text: &str, escaped_byte_idxs: &[usize], syntaxes: &[VariableSyntax], resolver: &dyn VariableResolver)
+    -> String
+{
+    let dollar_enabled = syntaxes.contains(&VariableSyntax::Dollar);
+    let default_enabled = syntaxes.contains(&VariableSyntax::DollarBracesDefault);
+    let braces_enabled = default_enabled || syntaxes.contains(&VariableSyntax::DollarBraces);
+    let percent_enabled = syntaxes.contains(&VariableSyntax::Percent);
+
+    let mut expanded = String::with_capacity(text.len());
+    let mut byte_idx = 0;
+    while let Some(unicode_char) = text[byte_idx..].chars().next() {
+        let rest = &text[byte_idx..];
+        let mut reference: Option<(usize, Option<String>)> = None; // (reference length, value)
+
+        if !escaped_byte_idxs.contains(&byte_idx) {
+            if unicode_char == '$' {
+                if braces_enabled && rest.starts_with("${") {
+                    if let Some(close_idx) = rest.find('}') {
+                        let content = &rest[2..close_idx];
+                        let (name, default) = match content.split_once(":-") {
+                            Some((name, default)) if default_enabled => (name, Some(default)),
+                            _ => (content, None),
+                        };
+                        if is_variable_name(name) {
+                            let value = match resolver.resolve(name) {
+                                Some(value) if !(value.is_empty() && default.is_some()) => Some(value),
+                                _ => default.map(String::from),
+                            };
+                            reference = Some((close_idx + 1, value));
+                        }
+                    }
+                } else {
+                    if dollar_enabled {
+                        let name_len = rest[1..].find(|name_char: char| !is_variable_name_char(name_char)).unwrap_or(rest.len() - 1);
+                        let name = &rest[1..=name_len];
+                        if is_variable_name(name) {
+                            reference = Some((name_len + 1, resolver.resolve(name)));
+                        }
+                    }
+                }
+            } else {
+                if unicode_char == '%' && percent_enabled {
+                    if let Some(close_idx) = rest[1..].find('%') {
+                        let name = &rest[1..=close_idx];
+                        if !name.is_empty() && !name.chars().any(|name_char| name_char.is_whitespace()) {
+                            reference = Some((close_idx + 2, resolver.resolve(name)));
+                        }
+                    }
+                }
+            }
+        }
+
+        match reference {
+            Some((reference_len, Some(value))) => {
+                expanded.push_str(&value);
+                byte_idx += reference_len;
+            }
+            Some((reference_len, None)) => {
+                expanded.push_str(&rest[..reference_len]);
+                byte_idx += reference_len;
+            }
+            None => {
+                expanded.push(unicode_char);
+                byte_idx += unicode_char.len_utf8();
+            }
+        }
+    }
+
+    expanded
+}
+
+fn is_variable_name_char(unicode_char: char) -> bool {
+    unicode_char.is_ascii_alphanumeric() || unicode_char == '_'
+}
+
+fn is_variable_name(name: &str) -> bool {
+    match name.chars().next() {
+        None => false,
+        Some(first_char) => (first_char.is_ascii_alphabetic() || first_char == '_') && name.chars().all(is_variable_name_char),
+    }
+}
@@ -0,0 +1,97 @@
+use parmacl::{Parser, Arg, RegexOrText, OptionHasValue, ParseErrorTypeId};
+
+fn create_parser() -> Parser {
+    let mut parser: Parser = Parser::new();
+    parser
+        .set_multi_char_option_code_requires_double_announcer(true)
+        .set_option_code_abbreviations(true);
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v"), RegexOrText::with_text("verbose")]);
+    parser
+        .push_new_option_matcher("version")
+            .some_option_codes(&[RegexOrText::with_text("version")]);
+    parser
+        .push_new_option_matcher("output")
+            .some_option_codes(&[RegexOrText::with_text("output"), RegexOrText::with_text("out")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+}
+
+#[test]
+fn abbreviated_option_codes() {
+    let parser = create_parser();
+
+    let args = parser.parse_line("bin --verb --VERSI --outp x --out y").unwrap();
+    assert_eq!(args.len(), 5);
+
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.matcher.name(), "verbose");
+            assert_eq!(properties.code, "verbose");
+            assert_eq!(properties.raw_text, "--verb");
+        },
+        _ => panic!("Expected option"),
+    }
+    match &args[2] {
+        Arg::Option(properties) => assert_eq!(properties.matcher.name(), "version"),
+        _ => panic!("Expected option"),
+    }
+    match &args[3] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.code, "output");
+            assert_eq!(properties.value_text.as_ref().unwrap(), "x");
+        },
+        _ => panic!("Expected option"),
+    }
+    match &args[4] {
+        // exact code preferred over abbreviation
+        Arg::Option(properties) => assert_eq!(properties.code, "out"),
+        _ => panic!("Expected option"),
+    }
+
+    let error = parser.parse_line("bin --ver").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::AmbiguousOption));
+    assert_eq!(error.option_code.as_deref(), Some("ver"));
+    assert_eq!(error.context.candidates, vec!["verbose", "version"]);
+
+    let error = parser.parse_line("bin --verbosely").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::UnmatchedOption));
+}
+
+#[test]
+fn abbreviations_disabled_by_default() {
+    let mut parser = create_parser();
+    parser.set_option_code_abbreviations(false);
+
+    let error = parser.parse_line("bin --verb").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::UnmatchedOption));
+    assert!(!Parser::<i32, i32>::new().option_code_abbreviations());
+}
+
+#[test]
+fn single_announced_option_codes_not_abbreviations() {
+    let parser = create_parser();
+
+    // multi char option codes require a double announcer so -o is not an abbreviation of --output
+    let error = parser.parse_line("bin -o x").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::UnmatchedOption));
+    assert_eq!(error.option_code.as_deref(), Some("o"));
+
+    let args = parser.parse_line("bin --o x").unwrap();
+    match &args[1] {
+        Arg::Option(properties) => assert_eq!(properties.code, "output"),
+        _ => panic!("Expected option"),
+    }
+
+    // without the double announcer requirement, single char option codes are not abbreviations
+    let mut parser = create_parser();
+    parser.set_multi_char_option_code_requires_double_announcer(false);
+    let error = parser.parse_line("bin -o x").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::UnmatchedOption));
+    let args = parser.parse_line("bin -outp x").unwrap();
+    match &args[1] {
+        Arg::Option(properties) => assert_eq!(properties.code, "output"),
+        _ => panic!("Expected option"),
+    }
+}
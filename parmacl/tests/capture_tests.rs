@@ -0,0 +1,132 @@
+use regex::Regex;
+use parmacl::{Parser, Arg, RegexOrText, OptionHasValue};
+
+#[test]
+fn option_code_captures() {
+    let mut parser: Parser = Parser::new();
+    parser.set_multi_char_option_code_requires_double_announcer(true);
+    parser
+        .push_new_option_matcher("optimize")
+            .some_option_codes(&[RegexOrText::with_regex(Regex::new(r"^O(\d)$").unwrap())]);
+    parser
+        .push_new_option_matcher("feature")
+            .some_option_codes(&[RegexOrText::with_text("f"), RegexOrText::with_regex(Regex::new(r"^feature-(?P<name>\w+)$").unwrap())])
+            .some_negation_prefix("no-");
+
+    let args = parser.parse_line("bin --O2 --feature-color --no-feature-sound -f").unwrap();
+    assert_eq!(args.len(), 5);
+
+    let captures: Vec<_> = args.iter().skip(1).map(|arg| match arg {
+        Arg::Option(properties) => properties.code_captures(),
+        _ => panic!("Expected option"),
+    }).collect();
+
+    let optimize_captures = captures[0].as_ref().unwrap();
+    assert_eq!(optimize_captures.len(), 2);
+    assert_eq!(optimize_captures.get(0), Some("O2"));
+    assert_eq!(optimize_captures.get(1), Some("2"));
+    assert_eq!(optimize_captures.get(2), None);
+
+    assert_eq!(captures[1].as_ref().unwrap().name("name"), Some("color"));
+    // negated code is captured without the negation prefix
+    assert_eq!(captures[2].as_ref().unwrap().name("name"), Some("sound"));
+    // matched by text code
+    assert!(captures[3].is_none());
+}
+
+#[test]
+fn param_value_captures() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_param_matcher("address")
+            .some_value_text(RegexOrText::with_regex(Regex::new(r"^(?P<host>[^:]+):(?P<port>\d+)$").unwrap()));
+    parser
+        .push_new_param_matcher("file");
+
+    let args = parser.parse_line("bin example.com:8080 readme").unwrap();
+    assert_eq!(args.len(), 3);
+
+    match &args[1] {
+        Arg::Param(properties) => {
+            assert_eq!(properties.matcher.name(), "address");
+            let captures = properties.value_captures().unwrap();
+            assert_eq!(captures.name("host"), Some("example.com"));
+            assert_eq!(captures.name("port"), Some("8080"));
+            assert_eq!(captures.name("user"), None);
+            let named: Vec<(&str, Option<&str>)> = captures.named().collect();
+            assert_eq!(named, [("host", Some("example.com")), ("port", Some("8080"))]);
+        }
+        _ => panic!("Expected param"),
+    }
+
+    match &args[2] {
+        Arg::Param(properties) => {
+            assert_eq!(properties.matcher.name(), "file");
+            assert!(properties.value_captures().is_none());
+        }
+        _ => panic!("Expected param"),
+    }
+}
+
+#[test]
+fn option_value_captures() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("size")
+            .some_option_codes(&[RegexOrText::with_text("s")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_value_text(RegexOrText::with_regex(Regex::new(r"^(\d+)x(\d+)$").unwrap()));
+
+    let args = parser.parse_line("bin -s 640X480").unwrap();
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert!(properties.code_captures().is_none());
+            let captures = properties.value_captures().unwrap();
+            // option values are not case sensitive by default
+            assert_eq!(captures.get(1), Some("640"));
+            assert_eq!(captures.get(2), Some("480"));
+        }
+        _ => panic!("Expected option"),
+    }
+}
+
+#[test]
+fn fallback_value_captures() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_param_matcher("address")
+            .some_default_value("localhost:80")
+            .some_value_text(RegexOrText::with_regex(Regex::new(r"^(?P<host>[^:]+):(?P<port>\d+)$").unwrap()));
+
+    let args = parser.parse_line("bin").unwrap();
+    assert_eq!(args.len(), 2);
+    match &args[1] {
+        Arg::Param(properties) => {
+            let captures = properties.value_captures().unwrap();
+            assert_eq!(captures.name("host"), Some("localhost"));
+            assert_eq!(captures.name("port"), Some("80"));
+        }
+        _ => panic!("Expected param"),
+    }
+}
+
+#[test]
+fn option_value_captures_after_choice_resolved() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("mode")
+            .some_option_codes(&[RegexOrText::with_text("m")])
+            .set_option_has_value(OptionHasValue::Always)
+            .set_choices(&["Fast", "Slow"])
+            .some_value_text(RegexOrText::with_regex(Regex::new(r"^(?P<speed>[a-z]+)$").unwrap()));
+
+    // captures are from the value with the choice's spelling
+    let args = parser.parse_line("bin -m FAST").unwrap();
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.value_text.as_deref(), Some("Fast"));
+            assert_eq!(properties.value_captures().unwrap().name("speed"), Some("Fast"));
+        }
+        _ => panic!("Expected option"),
+    }
+}
@@ -0,0 +1,64 @@
+use parmacl::{Parser, Arg, RegexOrText, OptionHasValue, ParseErrorTypeId};
+
+#[test]
+fn option_choices() {
+    let mut parser: Parser = Parser::new();
+    parser.set_multi_char_option_code_requires_double_announcer(true);
+    parser
+        .push_new_option_matcher("color")
+            .some_option_codes(&[RegexOrText::with_text("color"), RegexOrText::with_text("colour")])
+            .set_option_has_value(OptionHasValue::Always)
+            .set_choices(&["auto", "always", "never"]);
+    parser
+        .push_new_option_matcher("formats")
+            .some_option_codes(&[RegexOrText::with_text("formats")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_value_delimiter(',')
+            .set_choices(&["JSON", "Yaml"]);
+
+    let args = parser.parse_line("bin --color ALWAYS --formats json,YAML").unwrap();
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.value_text.as_ref().unwrap(), "always");
+            assert_eq!(properties.values, vec!["always"]);
+            assert_eq!(properties.value_raw_text.as_ref().unwrap(), "ALWAYS");
+        },
+        _ => panic!("Expected option"),
+    }
+    match &args[2] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.value_text.as_ref().unwrap(), "JSON,Yaml");
+            assert_eq!(properties.values, vec!["JSON", "Yaml"]);
+        },
+        _ => panic!("Expected option"),
+    }
+
+    let error = parser.parse_line("bin --colour sometimes").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::InvalidChoice));
+    assert_eq!(error.option_code.as_deref(), Some("colour"));
+    assert_eq!(error.param_value_text, "sometimes");
+    assert_eq!(error.context.candidates, vec!["auto", "always", "never"]);
+    assert!(error.to_string().contains("candidates:auto|always|never"));
+
+    parser.set_option_values_case_sensitive(true);
+    let error = parser.parse_line("bin --color Never").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::InvalidChoice));
+}
+
+#[test]
+fn choice_help_and_completion() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("color")
+            .some_option_codes(&[RegexOrText::with_text("c")])
+            .set_option_has_value(OptionHasValue::Always)
+            .set_choices(&["auto", "always", "never"]);
+
+    assert_eq!(parser.matchers()[0].choices_help_text().unwrap(), "auto|always|never");
+    assert_eq!(parser.complete_option_value("c", "A"), vec!["auto", "always"]);
+    assert_eq!(parser.complete_option_value("c", ""), vec!["auto", "always", "never"]);
+    assert!(parser.complete_option_value("x", "a").is_empty());
+
+    parser.set_option_values_case_sensitive(true);
+    assert!(parser.complete_option_value("c", "A").is_empty());
+}
@@ -0,0 +1,86 @@
+use parmacl::{Parser, Arg, RegexOrText, Span, ParseErrorTypeId};
+
+#[test]
+fn parse_commands() {
+    const COMMAND_LINE: &str = r#"load "a;b" ; run -v && save "x ""&&"" y"|wc # ; done"#;
+
+    let mut parser: Parser = Parser::new();
+    parser.set_parse_terminate_chars(&['#']);
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v")]);
+    parser
+        .push_new_param_matcher("param");
+
+    let commands = parser.parse_commands(COMMAND_LINE).unwrap();
+    assert_eq!(commands.len(), 4);
+
+    assert_eq!(commands[0].args.len(), 2);
+    assert_eq!(commands[0].separator.as_deref(), Some(";"));
+    assert_eq!(commands[0].separator_span, Some(Span::new(11, 12, 11, 12)));
+    match &commands[0].args[1] {
+        Arg::Param(properties) => {
+            assert_eq!(properties.value_text, "a;b");
+            assert_eq!(properties.span, Span::new(5, 10, 5, 10));
+        },
+        _ => panic!("Expected param"),
+    }
+
+    assert_eq!(commands[1].separator.as_deref(), Some("&&"));
+    match &commands[1].args[0] {
+        Arg::Binary(properties) => {
+            assert_eq!(properties.value_text, "run");
+            assert_eq!(properties.char_index, 13);
+        },
+        _ => panic!("Expected binary"),
+    }
+    match &commands[1].args[1] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.code, "v");
+            assert_eq!(properties.arg_index, 1);
+            assert_eq!(properties.raw_text, "-v");
+        },
+        _ => panic!("Expected option"),
+    }
+
+    assert_eq!(commands[2].separator.as_deref(), Some("|"));
+    match &commands[2].args[1] {
+        Arg::Param(properties) => assert_eq!(properties.value_text, r#"x "&&" y"#),
+        _ => panic!("Expected param"),
+    }
+
+    assert_eq!(commands[3].args.len(), 1);
+    assert_eq!(commands[3].separator, None);
+    assert_eq!(commands[3].termination.as_ref().unwrap().remainder, " ; done");
+
+    let error = parser.parse_commands("load a; run -x").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::UnmatchedOption));
+    assert_eq!(error.context.span.char_index, 12);
+}
+
+#[test]
+fn parse_commands_with_trailing_separator() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_param_matcher("param");
+
+    let commands = parser.parse_commands("b1 x; b2 && ").unwrap();
+    assert_eq!(commands.len(), 2);
+    assert_eq!(commands[0].separator.as_deref(), Some(";"));
+    assert_eq!(commands[1].args.len(), 1);
+    assert_eq!(commands[1].span, Span::new(5, 9, 5, 9));
+    assert_eq!(commands[1].separator, None);
+    assert_eq!(commands[1].separator_span, None);
+
+    let commands = parser.parse_commands("b1 x;").unwrap();
+    assert_eq!(commands.len(), 1);
+    assert_eq!(commands[0].args.len(), 2);
+    assert_eq!(commands[0].separator, None);
+    assert_eq!(commands[0].separator_span, None);
+
+    let commands = parser.parse_commands(";").unwrap();
+    assert_eq!(commands.len(), 1);
+    assert!(commands[0].args.is_empty());
+    assert_eq!(commands[0].separator, None);
+    assert_eq!(commands[0].separator_span, None);
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use parmacl::{Parser, Arg, RegexOrText, OptionHasValue, ValueSource, ConfigFile, ConfigFileError, ConfigLocation, ParseErrorTypeId};
+
+const BASE_CONFIG: &str = r#"
+# base settings
+output = base.txt
+level: 1
+title = "My \"Tool\"" # comment
+
+[server]
+host = 'localhost # not a comment'
+"#;
+
+const USER_CONFIG: &str = "; user overrides\nlevel = 2 # comment\n";
+
+fn create_parser() -> Parser {
+    let mut parser: Parser = Parser::new();
+    parser.set_env_var_resolver(HashMap::<String, String>::new());
+    parser
+        .push_new_option_matcher("output")
+            .some_option_codes(&[RegexOrText::with_text("o")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_default_value("default.txt");
+    parser
+        .push_new_option_matcher("level")
+            .some_option_codes(&[RegexOrText::with_text("l")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_env_var_name("LEVEL")
+            .some_default_value("0");
+    parser
+        .push_new_option_matcher("title")
+            .some_option_codes(&[RegexOrText::with_text("t")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+        .push_new_option_matcher("server.host")
+            .some_option_codes(&[RegexOrText::with_text("h")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+}
+
+#[test]
+fn config_file_precedence() {
+    let base = ConfigFile::parse("base.ini", BASE_CONFIG).unwrap();
+    assert_eq!(base.entries().len(), 4);
+    assert_eq!(base.find_entry("server.host").unwrap().value, "localhost # not a comment");
+    assert_eq!(base.find_entry("title").unwrap().value, r#"My "Tool""#);
+
+    let user = ConfigFile::parse("user.ini", USER_CONFIG).unwrap();
+
+    let mut parser = create_parser();
+    parser.push_config_file(base).push_config_file(user);
+
+    let args = parser.parse_line("tool -o cli.txt").unwrap();
+    assert_eq!(args.len(), 5);
+
+    let values: Vec<(&str, &str, &ValueSource)> = args.iter().skip(1).map(|arg| match arg {
+        Arg::Option(properties) => (properties.matcher.name(), properties.value_text.as_deref().unwrap(), &properties.value_source),
+        _ => panic!("Expected option"),
+    }).collect();
+
+    assert_eq!(values[0], ("output", "cli.txt", &ValueSource::CommandLine));
+    assert_eq!(values[1], ("level", "2", &ValueSource::ConfigFile(ConfigLocation { path: String::from("user.ini"), line_number: 2 })));
+    assert_eq!(values[2], ("title", r#"My "Tool""#, &ValueSource::ConfigFile(ConfigLocation { path: String::from("base.ini"), line_number: 5 })));
+    assert_eq!(values[3], ("server.host", "localhost # not a comment",
+        &ValueSource::ConfigFile(ConfigLocation { path: String::from("base.ini"), line_number: 8 })));
+
+    let mut variables = HashMap::new();
+    variables.insert(String::from("LEVEL"), String::from("9"));
+    parser.set_env_var_resolver(variables).set_config_files(Vec::new());
+    let args = parser.parse_line("tool").unwrap();
+    match (&args[1], &args[2]) {
+        (Arg::Option(output), Arg::Option(level)) => {
+            assert_eq!(output.value_source, ValueSource::Default);
+            assert_eq!(level.value_text.as_deref(), Some("9"));
+            assert_eq!(level.value_source, ValueSource::Environment);
+        },
+        _ => panic!("Expected options"),
+    }
+}
+
+#[test]
+fn config_file_errors() {
+    let error = ConfigFile::parse("bad.ini", "a = 1\nno separator\n").unwrap_err();
+    match error {
+        ConfigFileError::Syntax { path, line_number, .. } => {
+            assert_eq!(path, "bad.ini");
+            assert_eq!(line_number, 2);
+        },
+        _ => panic!("Expected syntax error"),
+    }
+    assert!(ConfigFile::parse("bad.ini", "a = \"unclosed\n").is_err());
+    assert!(ConfigFile::parse("bad.ini", "[section\n").is_err());
+
+    let path = std::env::temp_dir().join(format!("parmacl_config_test_{}.toml", std::process::id()));
+    std::fs::write(&path, "title = \"a\\tb\"\n").unwrap();
+    let config_file = ConfigFile::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(config_file.entries()[0].value, "a\tb");
+    assert!(matches!(ConfigFile::read(&path), Err(ConfigFileError::Io(_, _))));
+}
+
+#[test]
+fn invalid_config_file_entries() {
+    let mut parser = create_parser();
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v")]);
+
+    parser.push_config_file(ConfigFile::parse("bad.ini", "level = 1\nserver.port = 80\n").unwrap());
+    let error = parser.parse_line("tool").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::UnknownConfigKey));
+    assert_eq!(error.param_value_text, "server.port");
+    assert_eq!(error.context.value_source, Some(ValueSource::ConfigFile(ConfigLocation { path: String::from("bad.ini"), line_number: 2 })));
+
+    // option which never has a value
+    parser.set_config_files(vec![ConfigFile::parse("bad.ini", "verbose = loud\n").unwrap()]);
+    let error = parser.parse_line("tool").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::InvalidFallbackValue));
+    assert_eq!(error.option_code.as_deref(), Some("v"));
+    assert_eq!(error.context.value_source, Some(ValueSource::ConfigFile(ConfigLocation { path: String::from("bad.ini"), line_number: 1 })));
+}
@@ -0,0 +1,92 @@
+use parmacl::{Parser, Arg, RegexOrText, OptionHasValue, LineFormatter};
+
+#[test]
+fn counted_options() {
+    let mut parser: Parser = Parser::new();
+    parser.set_multi_char_option_code_requires_double_announcer(true);
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v"), RegexOrText::with_text("verbose")])
+            .set_counted(true);
+    parser
+        .push_new_option_matcher("file")
+            .some_option_codes(&[RegexOrText::with_text("f")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+        .push_new_param_matcher("param");
+
+    let args = parser.parse_line("bin -v p1 -f x -VVV --verbose p2").unwrap();
+    assert_eq!(args.len(), 5);
+
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.matcher.name(), "verbose");
+            assert_eq!(properties.count, 5);
+            assert_eq!(properties.arg_index, 1);
+            assert_eq!(properties.option_index, 0);
+        },
+        _ => panic!("Expected option"),
+    }
+
+    match &args[3] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.matcher.name(), "file");
+            assert_eq!(properties.count, 1);
+            assert_eq!(properties.arg_index, 3);
+            assert_eq!(properties.option_index, 1);
+        },
+        _ => panic!("Expected option"),
+    }
+
+    match &args[4] {
+        Arg::Param(properties) => {
+            assert_eq!(properties.value_text, "p2");
+            assert_eq!(properties.arg_index, 4);
+        },
+        _ => panic!("Expected param"),
+    }
+
+    let formatter = LineFormatter::with_parser_style(&parser);
+    assert_eq!(formatter.format_args(&args), "bin -v -v -v -v -v p1 -f x p2");
+
+    // clustered form only applies to counted matchers
+    assert!(parser.parse_line("bin -ff x").is_err());
+    assert!(parser.parse_line("bin -vvf").is_err());
+}
+
+#[test]
+fn counted_options_with_fallback() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v")])
+            .set_counted(true);
+    parser
+        .push_new_option_matcher("output")
+            .some_option_codes(&[RegexOrText::with_text("o")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_default_value("out.txt");
+
+    let args = parser.parse_line("bin -v -v -v").unwrap();
+    assert_eq!(args.len(), 3);
+
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.matcher.name(), "verbose");
+            assert_eq!(properties.count, 3);
+            assert_eq!(properties.arg_index, 1);
+            assert_eq!(properties.option_index, 0);
+        },
+        _ => panic!("Expected option"),
+    }
+
+    match &args[2] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.matcher.name(), "output");
+            assert_eq!(properties.value_text.as_deref(), Some("out.txt"));
+            assert_eq!(properties.arg_index, 2);
+            assert_eq!(properties.option_index, 1);
+        },
+        _ => panic!("Expected option"),
+    }
+}
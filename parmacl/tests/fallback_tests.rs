@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use parmacl::{Parser, Arg, RegexOrText, OptionHasValue, MatchArgTypeId, ValueSource, ParseErrorTypeId};
+
+#[test]
+fn fallback_values() {
+    let mut variables = HashMap::new();
+    variables.insert(String::from("MYTOOL_OUTPUT"), String::from("env.txt"));
+
+    let mut parser: Parser = Parser::new();
+    parser.set_env_var_resolver(variables);
+    parser
+        .push_new_option_matcher("output")
+            .some_option_codes(&[RegexOrText::with_text("o"), RegexOrText::with_text("out")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_env_var_name("MYTOOL_OUTPUT")
+            .some_default_value("out.txt");
+    parser
+        .push_new_option_matcher("level")
+            .some_option_codes(&[RegexOrText::with_text("l")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_env_var_name("MYTOOL_LEVEL")
+            .some_default_value("3");
+    parser
+        .push_new_matcher("target")
+            .some_arg_type(MatchArgTypeId::Param)
+            .some_default_value("all");
+    parser
+        .push_new_param_matcher("other");
+
+    let args = parser.parse_line("tool -l 5").unwrap();
+    assert_eq!(args.len(), 4);
+
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.matcher.name(), "level");
+            assert_eq!(properties.value_text.as_deref(), Some("5"));
+            assert_eq!(properties.value_source, ValueSource::CommandLine);
+        },
+        _ => panic!("Expected option"),
+    }
+
+    match &args[2] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.matcher.name(), "output");
+            assert_eq!(properties.code, "o");
+            assert_eq!(properties.value_text.as_deref(), Some("env.txt"));
+            assert_eq!(properties.value_source, ValueSource::Environment);
+            assert_eq!(properties.arg_index, 2);
+            assert_eq!(properties.option_index, 1);
+            assert_eq!(properties.char_index, 9);
+            assert!(properties.span.is_empty());
+            assert_eq!(properties.value_raw_text, None);
+        },
+        _ => panic!("Expected option"),
+    }
+
+    match &args[3] {
+        Arg::Param(properties) => {
+            assert_eq!(properties.matcher.name(), "target");
+            assert_eq!(properties.value_text, "all");
+            assert_eq!(properties.value_source, ValueSource::Default);
+            assert_eq!(properties.param_index, 0);
+        },
+        _ => panic!("Expected param"),
+    }
+
+    let args = parser.parse_line("tool -o x.txt -l 1 everything").unwrap();
+    assert_eq!(args.len(), 4);
+    assert!(args.iter().all(|arg| match arg {
+        Arg::Binary(_) => true,
+        Arg::Param(properties) => properties.value_source == ValueSource::CommandLine,
+        Arg::Option(properties) => properties.value_source == ValueSource::CommandLine,
+    }));
+}
+
+#[test]
+fn fallback_env_var_is_resolved_from_environment() {
+    std::env::set_var("PARMACL_FALLBACK_TEST_LEVEL", "7");
+
+    let mut variables = HashMap::new();
+    variables.insert(String::from("PARMACL_FALLBACK_TEST_LEVEL"), String::from("1"));
+
+    let mut parser: Parser = Parser::new();
+    // variable resolver is only used for variable expansion
+    parser.set_variable_resolver(variables);
+    parser
+        .push_new_option_matcher("level")
+            .some_option_codes(&[RegexOrText::with_text("l")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_env_var_name("PARMACL_FALLBACK_TEST_LEVEL");
+
+    let args = parser.parse_line("tool").unwrap();
+    assert_eq!(args.len(), 2);
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.value_text.as_deref(), Some("7"));
+            assert_eq!(properties.value_source, ValueSource::Environment);
+        },
+        _ => panic!("Expected option"),
+    }
+}
+
+#[test]
+fn invalid_fallback_values() {
+    let mut variables = HashMap::new();
+    variables.insert(String::from("MYTOOL_LEVEL"), String::from("high"));
+
+    let mut parser: Parser = Parser::new();
+    parser.set_env_var_resolver(variables);
+    parser
+        .push_new_option_matcher("level")
+            .some_option_codes(&[RegexOrText::with_text("l")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_env_var_name("MYTOOL_LEVEL")
+            .some_value_text(RegexOrText::try_with_regex_pattern("^[0-9]$").unwrap());
+
+    let error = parser.parse_line("tool").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::InvalidFallbackValue));
+    assert_eq!(error.option_code.as_deref(), Some("l"));
+    assert_eq!(error.param_value_text, "high");
+    assert_eq!(error.context.value_source, Some(ValueSource::Environment));
+    // value in command line takes precedence
+    assert!(parser.parse_line("tool -l 5").is_ok());
+
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_param_matcher("count")
+            .some_default_value("many")
+            .some_value_text(RegexOrText::try_with_regex_pattern("^[0-9]+$").unwrap());
+    let error = parser.parse_line("tool").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::InvalidFallbackValue));
+    assert_eq!(error.param_index, Some(0));
+    assert_eq!(error.context.value_source, Some(ValueSource::Default));
+
+    // fallback value of an option which never has a value must be boolean
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v")])
+            .some_default_value("loud");
+    let error = parser.parse_line("tool").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::InvalidFallbackValue));
+    assert!(parser.parse_line("tool -v").is_ok());
+}
@@ -0,0 +1,78 @@
+use parmacl::{Parser, Arg, Args, DefaultTagType, RegexOrText, OptionHasValue, LineFormatter};
+
+#[test]
+fn canonical_line() {
+    const COMMAND_LINE: &str = r#"tool --OUT "my file.txt" -p secret "param ""one""" -v"#;
+
+    let mut parser: Parser = Parser::new();
+    parser.set_multi_char_option_code_requires_double_announcer(true);
+
+    parser
+        .push_new_option_matcher("output")
+            .some_option_codes(&[RegexOrText::with_text("output"), RegexOrText::with_text("out"), RegexOrText::with_text("o")])
+            .set_option_has_value(OptionHasValue::Always);
+
+    parser
+        .push_new_option_matcher("password")
+            .some_option_codes(&[RegexOrText::with_text("p")])
+            .set_option_has_value(OptionHasValue::Always)
+            .set_redact_value(true);
+
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v")]);
+
+    parser
+        .push_new_param_matcher("param");
+
+    let args = parser.parse_line(COMMAND_LINE).unwrap();
+
+    let mut formatter = LineFormatter::with_parser_style(&parser);
+    assert_eq!(formatter.format_args(&args), r#"tool --output "my file.txt" -p *** "param ""one""" -v"#);
+
+    formatter
+        .set_redact_values(false)
+        .set_always_quote_values(true)
+        .set_option_value_announcer_char('=');
+    assert_eq!(formatter.format_args(&args), r#""tool" --output="my file.txt" -p="secret" "param ""one""" -v"#);
+
+    formatter
+        .set_embed_quote_char_with_double(false)
+        .set_escape_char(Some('\\'));
+    assert_eq!(formatter.format_value(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+}
+
+fn arg_values(args: &Args<DefaultTagType, DefaultTagType>) -> Vec<String> {
+    args.iter().map(|arg| match arg {
+        Arg::Binary(properties) => format!("binary:{}", properties.value_text),
+        Arg::Param(properties) => format!("param:{}", properties.value_text),
+        Arg::Option(properties) => format!("option:{}={:?}", properties.code, properties.value_text),
+    }).collect()
+}
+
+#[test]
+fn format_parse_round_trip() {
+    const COMMAND_LINE: &str = r##"bin "a""b" ab"c "#x" "x#y" "+x" "a;b" "a|b" "a b" "" 'it"s' -o "-v" -o 'q"'"##;
+
+    let mut parser: Parser = Parser::new();
+    parser
+        .set_quote_chars(&['"', '\''])
+        .set_option_announcer_chars(&['-', '+'])
+        .set_parse_terminate_chars(&['#']);
+    parser
+        .push_new_option_matcher("output")
+            .some_option_codes(&[RegexOrText::with_text("o")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+        .push_new_param_matcher("param");
+
+    let args = parser.parse_line(COMMAND_LINE).unwrap();
+    assert_eq!(args.len(), 13);
+
+    let formatter = LineFormatter::with_parser_style(&parser);
+    let formatted_line = formatter.format_args(&args);
+    assert_eq!(formatted_line, r##"bin "a""b" "ab""c" "#x" "x#y" "+x" "a;b" "a|b" "a b" "" "it""s" -o "-v" -o "q""""##);
+
+    let reparsed_args = parser.parse_line(&formatted_line).unwrap();
+    assert_eq!(arg_values(&reparsed_args), arg_values(&args));
+}
@@ -0,0 +1,141 @@
+use parmacl::{Parser, Arg, RegexOrText, RegexOptions, OptionHasValue};
+
+#[test]
+fn glob_wildcards() {
+    let glob = RegexOrText::with_glob("*.txt");
+    assert!(glob.is_glob());
+    assert!(glob.is_regex());
+    assert_eq!(glob.text(), "*.txt");
+    assert!(glob.is_match("readme.txt", true));
+    assert!(glob.is_match(".txt", true));
+    assert!(glob.is_match("dir/notes.txt", true));
+    assert!(!glob.is_match("readme.txt.bak", true));
+    assert!(!glob.is_match("readme_txt", true));
+
+    let glob = RegexOrText::with_glob("log-??");
+    assert!(glob.is_match("log-01", true));
+    assert!(glob.is_match("log-ab", true));
+    assert!(!glob.is_match("log-1", true));
+    assert!(!glob.is_match("log-123", true));
+
+    // regex special characters match themselves
+    let glob = RegexOrText::with_glob("a+b(1)$");
+    assert!(glob.is_match("a+b(1)$", true));
+    assert!(!glob.is_match("aab(1)", true));
+}
+
+#[test]
+fn glob_brackets() {
+    let glob = RegexOrText::with_glob("[a-c]*");
+    assert!(glob.is_match("apple", true));
+    assert!(glob.is_match("cherry", true));
+    assert!(!glob.is_match("date", true));
+
+    let glob = RegexOrText::with_glob("file[!0-9]");
+    assert!(glob.is_match("fileA", true));
+    assert!(!glob.is_match("file5", true));
+
+    let glob = RegexOrText::with_glob("v[^.]");
+    assert!(glob.is_match("v1", true));
+    assert!(!glob.is_match("v.", true));
+
+    // special characters in brackets match literally
+    let glob = RegexOrText::with_glob("[*?]x");
+    assert!(glob.is_match("*x", true));
+    assert!(glob.is_match("?x", true));
+    assert!(!glob.is_match("ax", true));
+
+    let glob = RegexOrText::with_glob("[]-]");
+    assert!(glob.is_match("]", true));
+    assert!(glob.is_match("-", true));
+    assert!(!glob.is_match("a", true));
+
+    // reversed range is not a range
+    let glob = RegexOrText::try_with_glob("[z-a]").unwrap();
+    assert!(glob.is_match("z", true));
+    assert!(glob.is_match("-", true));
+    assert!(glob.is_match("a", true));
+    assert!(!glob.is_match("m", true));
+
+    // unclosed bracket matches itself
+    let glob = RegexOrText::with_glob("[ab");
+    assert!(glob.is_match("[ab", true));
+    assert!(!glob.is_match("a", true));
+}
+
+#[test]
+fn glob_case_sensitivity() {
+    let mut glob = RegexOrText::with_glob("*.TXT");
+    assert!(glob.is_match("readme.txt", false));
+    assert!(!glob.is_match("readme.txt", true));
+
+    glob.set_override_case_sensitive(Some(true));
+    assert!(!glob.is_match("readme.txt", false));
+    assert!(glob.is_match("readme.TXT", false));
+}
+
+#[test]
+fn glob_set_and_reset() {
+    let mut regex_or_text = RegexOrText::with_text("*.txt");
+    assert!(!regex_or_text.is_glob());
+    assert!(!regex_or_text.is_match("a.txt", true));
+
+    regex_or_text.set_glob("*.txt");
+    assert!(regex_or_text.is_glob());
+    assert!(regex_or_text.is_match("a.txt", true));
+    // globs have no capture groups
+    assert!(regex_or_text.captures("a.txt", true).is_none());
+
+    regex_or_text.try_set_regex_pattern(r"^\w+\.txt$").unwrap();
+    assert!(!regex_or_text.is_glob());
+    assert!(regex_or_text.is_regex());
+
+    regex_or_text.set_glob("*.txt");
+    let mut options = RegexOptions::new();
+    options.some_size_limit(10);
+    assert!(regex_or_text.try_set_regex_options(options).is_err());
+    assert!(regex_or_text.is_glob());
+
+    regex_or_text.set_text("*.txt");
+    assert!(!regex_or_text.is_glob());
+    assert!(!regex_or_text.is_regex());
+    assert!(regex_or_text.is_match("*.txt", true));
+}
+
+fn create_parser() -> Parser {
+    let mut parser: Parser = Parser::new();
+    parser.set_multi_char_option_code_requires_double_announcer(true);
+    parser
+        .push_new_option_matcher("log-level")
+            .some_option_codes(&[RegexOrText::with_glob("log-*")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+        .push_new_param_matcher("text-file")
+            .some_value_text(RegexOrText::with_glob("*.txt"));
+    parser
+        .push_new_param_matcher("other-file");
+    parser
+}
+
+fn matcher_names(parser: &Parser, line: &str) -> Vec<String> {
+    parser.parse_line(line).unwrap().iter().map(|arg| match arg {
+        Arg::Binary(_) => String::from("binary"),
+        Arg::Param(properties) => String::from(properties.matcher.name()),
+        Arg::Option(properties) => String::from(properties.matcher.name()),
+    }).collect()
+}
+
+#[test]
+fn glob_matchers() {
+    let line = "bin --log-net debug notes.txt image.png README.TXT";
+    let expected = ["binary", "log-level", "text-file", "other-file", "text-file"];
+
+    let parser = create_parser();
+    assert_eq!(matcher_names(&parser, line), expected);
+    assert!(parser.parse_line("bin --verbose").is_err());
+
+    let mut compiled_parser = create_parser();
+    compiled_parser.compile();
+    assert_eq!(matcher_names(&compiled_parser, line), expected);
+    assert!(compiled_parser.parse_line("bin --verbose").is_err());
+}
@@ -0,0 +1,90 @@
+use parmacl::{Parser, Arg, RegexOrText, OptionHasValue, OptionValueArity, ParseErrorTypeId, MapDuplicates};
+
+fn create_parser() -> Parser {
+    let mut parser: Parser = Parser::new();
+    parser.set_parse_terminate_chars(&['#']);
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v")]);
+    parser
+        .push_new_option_matcher("point")
+            .some_option_codes(&[RegexOrText::with_text("p")])
+            .set_option_has_value(OptionHasValue::Always)
+            .set_option_value_arity(OptionValueArity::Exactly(2));
+    parser
+        .push_new_param_matcher("param");
+    parser
+}
+
+#[test]
+fn iter_line() {
+    let parser = create_parser();
+    const LINE: &str = "bin -v build -p 1 2 \"a b\" # rest";
+
+    let mut iter = parser.iter_line(LINE);
+    let values: Vec<String> = iter.by_ref().map(|arg| match arg.unwrap() {
+        Arg::Binary(properties) => format!("b:{}", properties.value_text),
+        Arg::Option(properties) => format!("o:{}={}", properties.code, properties.values.join(",")),
+        Arg::Param(properties) => format!("p:{}", properties.value_text),
+    }).collect();
+    assert_eq!(values, ["b:bin", "o:v=", "p:build", "o:p=1,2", "p:a b"]);
+    assert_eq!(iter.termination().unwrap().remainder, " rest");
+
+    // same arguments as parse_line
+    let args = parser.parse_line(LINE).unwrap();
+    let iter_args: Vec<_> = parser.iter_line(LINE).map(|arg| arg.unwrap()).collect();
+    assert_eq!(format!("{:?}", iter_args), format!("{:?}", args));
+}
+
+#[test]
+fn iter_line_stop_early() {
+    let parser = create_parser();
+
+    // the unmatched option after the subcommand is never parsed
+    let subcommand = parser.iter_line("bin -v build -x").find_map(|arg| match arg.unwrap() {
+        Arg::Param(properties) => Some(properties.value_text),
+        _ => None,
+    });
+    assert_eq!(subcommand.as_deref(), Some("build"));
+
+    let mut iter = parser.iter_line("bin build -x");
+    assert!(matches!(iter.next(), Some(Ok(Arg::Binary(_)))));
+    assert!(matches!(iter.next(), Some(Ok(Arg::Param(_)))));
+    match iter.next() {
+        Some(Err(error)) => assert!(matches!(error.type_id, ParseErrorTypeId::UnmatchedOption)),
+        _ => panic!("Expected error"),
+    }
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn iter_line_counted_and_map_options() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v")])
+            .set_counted(true);
+    parser
+        .push_new_option_matcher("define")
+            .some_option_codes(&[RegexOrText::with_text("D")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_value_delimiter(',')
+            .some_map_separator('=')
+            .set_map_duplicates(MapDuplicates::Error);
+
+    // parse_line merges counted options and detects duplicate keys across options
+    const LINE: &str = "bin -v -vv -D a=1 -D a=2";
+    let error = parser.parse_line(LINE).unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::DuplicateMapKey));
+
+    // iter_line returns each counted option separately and only detects duplicate keys within an option
+    let values: Vec<String> = parser.iter_line(LINE).map(|arg| match arg.unwrap() {
+        Arg::Binary(properties) => format!("b:{}", properties.value_text),
+        Arg::Option(properties) => format!("o:{}:{}:{}", properties.code, properties.count, properties.values.join(",")),
+        Arg::Param(properties) => format!("p:{}", properties.value_text),
+    }).collect();
+    assert_eq!(values, ["b:bin", "o:v:1:", "o:vv:2:", "o:D:1:a=1", "o:D:1:a=2"]);
+
+    let error = parser.iter_line("bin -D a=1,a=2").find_map(|arg| arg.err()).unwrap();
+    assert!(matches!(error.type_id, ParseErrorTypeId::DuplicateMapKey));
+}
@@ -1,4 +1,4 @@
-use parmacl::{Parser, Arg, RegexOrText, OptionHasValue};
+use parmacl::{Parser, Arg, RegexOrText, OptionHasValue, Span, ParseErrorTypeId};
 #[derive(Default)]
 enum OptionEnum {
     #[default] A,
@@ -92,6 +92,137 @@ fn no_matchers() {
     }
 }
 
+#[test]
+fn multi_char_option_code_requires_double_announcer() {
+    let mut parser: Parser = Parser::new();
+    parser.set_multi_char_option_code_requires_double_announcer(true);
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v"), RegexOrText::with_text("verbose")]);
+
+    // single character code with one announcer and multi character code with two announcers
+    let args = parser.parse_line("bin -v --verbose").unwrap();
+    assert_eq!(args.len(), 3);
+    for arg in args.iter().skip(1) {
+        match arg {
+            Arg::Option(properties) => assert_eq!(properties.matcher.name(), "verbose"),
+            _ => panic!("Expected option"),
+        }
+    }
+    match &args[2] {
+        Arg::Option(properties) => assert_eq!(properties.code, "verbose"),
+        _ => panic!("Expected option"),
+    }
+
+    // multi character code with one announcer
+    let error = parser.parse_line("bin -verbose").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::OptionCodeMissingDoubleAnnouncer));
+    assert_eq!(error.option_code.as_deref(), Some("verbose"));
+
+    // double announcer is not required when false
+    parser.set_multi_char_option_code_requires_double_announcer(false);
+    let args = parser.parse_line("bin -v -verbose").unwrap();
+    assert_eq!(args.len(), 3);
+}
+
+#[test]
+fn repeated_whitespace_between_args() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v")]);
+    parser
+        .push_new_param_matcher("param");
+
+    let args = parser.parse_line(" \tbin  -v \t p1   p2 ").unwrap();
+    assert_eq!(args.len(), 4);
+    let texts: Vec<&str> = args.iter().map(|arg| match arg {
+        Arg::Binary(properties) => properties.value_text.as_ref(),
+        Arg::Option(properties) => properties.code.as_ref(),
+        Arg::Param(properties) => properties.value_text.as_ref(),
+    }).collect();
+    assert_eq!(texts, ["bin", "v", "p1", "p2"]);
+    match &args[2] {
+        Arg::Param(properties) => assert_eq!(properties.char_index, 12),
+        _ => panic!("Expected param"),
+    }
+}
+
+#[test]
+fn param_error_indices() {
+    let parser: Parser = Parser::new();
+
+    let error = parser.parse_line(r#"bin -a -b p1 "unclosed"#).unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::ParamMissingClosingQuoteCharacter));
+    assert_eq!(error.arg_index, 4);
+    assert_eq!(error.param_index, Some(1));
+    assert_eq!(error.option_index, None);
+}
+
+#[test]
+fn if_possible_option_value_after_whitespace() {
+    let mut parser: Parser = Parser::new();
+    parser.set_option_value_announcer_chars(&[' ', '=']);
+    parser
+        .push_new_option_matcher("level")
+            .some_option_codes(&[RegexOrText::with_text("l")])
+            .set_option_has_value(OptionHasValue::IfPossible)
+            .some_value_text(RegexOrText::with_text("5"));
+    parser
+        .push_new_param_matcher("param");
+
+    let args = parser.parse_line("bin -l 5").unwrap();
+    assert_eq!(args.len(), 2);
+    match &args[1] {
+        Arg::Option(properties) => assert_eq!(properties.value_text.as_deref(), Some("5")),
+        _ => panic!("Expected option"),
+    }
+
+    // whitespace announced value which does not match is a param
+    let args = parser.parse_line("bin -l file").unwrap();
+    assert_eq!(args.len(), 3);
+    match &args[1] {
+        Arg::Option(properties) => assert_eq!(properties.value_text, None),
+        _ => panic!("Expected option"),
+    }
+    match &args[2] {
+        Arg::Param(properties) => assert_eq!(properties.value_text, "file"),
+        _ => panic!("Expected param"),
+    }
+
+    // explicitly announced value must match
+    assert!(parser.parse_line("bin -l=file").is_err());
+}
+
+#[test]
+fn escape_char_at_start_of_value() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .some_escape_char('\\')
+        .set_escapeable_chars(&['-']);
+    parser
+        .push_new_option_matcher("output")
+            .some_option_codes(&[RegexOrText::with_text("o")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+        .push_new_param_matcher("param");
+
+    let args = parser.parse_line(r"bin \-p1 \\p2 -o \-v").unwrap();
+    assert_eq!(args.len(), 4);
+    match &args[1] {
+        Arg::Param(properties) => assert_eq!(properties.value_text, "-p1"),
+        _ => panic!("Expected param"),
+    }
+    match &args[2] {
+        Arg::Param(properties) => assert_eq!(properties.value_text, r"\p2"),
+        _ => panic!("Expected param"),
+    }
+    match &args[3] {
+        Arg::Option(properties) => assert_eq!(properties.value_text.as_deref(), Some("-v")),
+        _ => panic!("Expected option"),
+    }
+}
+
 const BASIC_MATCHERS_COMMAND_LINE: &str = "\
 \"binary name\" \
 param1 \
@@ -305,35 +436,35 @@ fn basic_matchers() {
                         assert_eq!(properties.arg_index, 6);
                         assert_eq!(properties.option_index, 2);
                         assert_eq!(properties.code, "c");
-                        assert_eq!(properties.value_text, Some(String::from("valueC_1")));
+                        assert_eq!(properties.value_text.as_deref(), Some("valueC_1"));
                         assert_eq!(properties.char_index, 41);
                     },
                     OptionEnum::D => {
                         assert_eq!(properties.arg_index, 9);
                         assert_eq!(properties.option_index, 3);
                         assert_eq!(properties.code, "d");
-                        assert_eq!(properties.value_text, Some(String::from("value D1")));
+                        assert_eq!(properties.value_text.as_deref(), Some("value D1"));
                         assert_eq!(properties.char_index, 72);
                     },
                     OptionEnum::E => {
                         assert_eq!(properties.arg_index, 10);
                         assert_eq!(properties.option_index, 4);
                         assert_eq!(properties.code, "e");
-                        assert_eq!(properties.value_text, Some(String::from("-valueE1")));
+                        assert_eq!(properties.value_text.as_deref(), Some("-valueE1"));
                         assert_eq!(properties.char_index, 86);
                     },
                     OptionEnum::F => {
                         assert_eq!(properties.arg_index, 11);
                         assert_eq!(properties.option_index, 5);
                         assert_eq!(properties.code, "F");
-                        assert_eq!(properties.value_text, Some(String::from("-value F1")));
+                        assert_eq!(properties.value_text.as_deref(), Some("-value F1"));
                         assert_eq!(properties.char_index, 98);
                     },
                     OptionEnum::G => {
                         assert_eq!(properties.arg_index, 12);
                         assert_eq!(properties.option_index, 6);
                         assert_eq!(properties.code, "g");
-                        assert_eq!(properties.value_text, Some(String::from("optvalueG1")));
+                        assert_eq!(properties.value_text.as_deref(), Some("optvalueG1"));
                         assert_eq!(properties.char_index, 113);
                     },
                     OptionEnum::Hh => {
@@ -347,56 +478,56 @@ fn basic_matchers() {
                         assert_eq!(properties.arg_index, 15);
                         assert_eq!(properties.option_index, 8);
                         assert_eq!(properties.code, "ii");
-                        assert_eq!(properties.value_text, Some(String::from("valueII1")));
+                        assert_eq!(properties.value_text.as_deref(), Some("valueII1"));
                         assert_eq!(properties.char_index, 138);
                     },
                     OptionEnum::Jj => {
                         assert_eq!(properties.arg_index, 16);
                         assert_eq!(properties.option_index, 9);
                         assert_eq!(properties.code, "JJ");
-                        assert_eq!(properties.value_text, Some(String::from("-optValueJJ1")));
+                        assert_eq!(properties.value_text.as_deref(), Some("-optValueJJ1"));
                         assert_eq!(properties.char_index, 151);
                     },
                     OptionEnum::Kkkk => {
                         assert_eq!(properties.arg_index, 17);
                         assert_eq!(properties.option_index, 10);
                         assert_eq!(properties.code, "kkkk");
-                        assert_eq!(properties.value_text, Some(String::from("valueKKKK1")));
+                        assert_eq!(properties.value_text.as_deref(), Some("valueKKKK1"));
                         assert_eq!(properties.char_index, 168);
                     },
                     OptionEnum::Ll => {
                         assert_eq!(properties.arg_index, 18);
                         assert_eq!(properties.option_index, 11);
                         assert_eq!(properties.code, "LL");
-                        assert_eq!(properties.value_text, Some(String::from("-optValueLL1")));
+                        assert_eq!(properties.value_text.as_deref(), Some("-optValueLL1"));
                         assert_eq!(properties.char_index, 187);
                     },
                     OptionEnum::M => {
                         assert_eq!(properties.arg_index, 19);
                         assert_eq!(properties.option_index, 12);
                         assert_eq!(properties.code, "m");
-                        assert_eq!(properties.value_text, Some(String::from("opt\"valueM")));
+                        assert_eq!(properties.value_text.as_deref(), Some("opt\"valueM"));
                         assert_eq!(properties.char_index, 206);
                     },
                     OptionEnum::N => {
                         assert_eq!(properties.arg_index, 20);
                         assert_eq!(properties.option_index, 13);
                         assert_eq!(properties.code, "n");
-                        assert_eq!(properties.value_text, Some(String::from("opt\"valueN")));
+                        assert_eq!(properties.value_text.as_deref(), Some("opt\"valueN"));
                         assert_eq!(properties.char_index, 223);
                     },
                     OptionEnum::O => {
                         assert_eq!(properties.arg_index, 21);
                         assert_eq!(properties.option_index, 14);
                         assert_eq!(properties.code, "o");
-                        assert_eq!(properties.value_text, Some(String::from("opt\"valueO\"")));
+                        assert_eq!(properties.value_text.as_deref(), Some("opt\"valueO\""));
                         assert_eq!(properties.char_index, 237);
                     },
                     OptionEnum::P => {
                         assert_eq!(properties.arg_index, 22);
                         assert_eq!(properties.option_index, 15);
                         assert_eq!(properties.code, "p");
-                        assert_eq!(properties.value_text, Some(String::from("opt\"\"valueP")));
+                        assert_eq!(properties.value_text.as_deref(), Some("opt\"\"valueP"));
                         assert_eq!(properties.char_index, 252);
                     },
                 }
@@ -468,3 +599,154 @@ fn basic_matchers() {
         }
     }
 }
+
+#[test]
+fn spans() {
+    const COMMAND_LINE: &str = r#"bïn -ä "välue 1" pärам -b "#;
+
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("optionA")
+            .some_option_codes(&[RegexOrText::with_text("ä")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+        .push_new_option_matcher("optionB")
+            .some_option_codes(&[RegexOrText::with_text("b")])
+            .set_option_has_value(OptionHasValue::IfPossible);
+    parser
+        .push_new_param_matcher("param");
+
+    let args = parser.parse_line(COMMAND_LINE).unwrap();
+    assert_eq!(args.len(), 4);
+
+    match &args[0] {
+        Arg::Binary(properties) => {
+            assert_eq!(properties.span, Span::new(0, 3, 0, 4));
+        },
+        _ => panic!("Expected binary"),
+    }
+
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.code, "ä");
+            assert_eq!(properties.char_index, 4);
+            assert_eq!(properties.span, Span::new(4, 16, 5, 19));
+            assert_eq!(properties.code_span, Span::new(4, 6, 5, 8));
+            assert_eq!(properties.value_span, Some(Span::new(7, 16, 9, 19)));
+            assert_eq!(&COMMAND_LINE[properties.span.byte_index..properties.span.end_byte_index], r#"-ä "välue 1""#);
+        },
+        _ => panic!("Expected option"),
+    }
+
+    match &args[2] {
+        Arg::Param(properties) => {
+            assert_eq!(properties.value_text, "pärам");
+            assert_eq!(properties.span, Span::new(17, 22, 20, 28));
+        },
+        _ => panic!("Expected param"),
+    }
+
+    match &args[3] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.span, Span::new(23, 25, 29, 31));
+            assert_eq!(properties.value_span, None);
+        },
+        _ => panic!("Expected option"),
+    }
+
+    let error = parser.parse_line(r#"bin "välue"x"#).unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::QuotedParamNotFollowedByWhitespaceChar));
+    assert_eq!(error.param_index, Some(0));
+    assert_eq!(error.context.span, Span::new(4, 11, 4, 12));
+}
+
+#[test]
+fn raw_text() {
+    const COMMAND_LINE: &str = r#"bin -o:"v ""1""" "1st ""Param""" p2 -f"#;
+
+    let mut parser: Parser = Parser::new();
+    parser.set_option_value_announcer_chars(&[':']);
+    parser
+        .push_new_option_matcher("option")
+            .some_option_codes(&[RegexOrText::with_text("o")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+        .push_new_option_matcher("flag")
+            .some_option_codes(&[RegexOrText::with_text("f")]);
+    parser
+        .push_new_param_matcher("param");
+
+    let args = parser.parse_line(COMMAND_LINE).unwrap();
+    assert_eq!(args.len(), 5);
+    match &args[0] {
+        Arg::Binary(properties) => assert_eq!(properties.raw_text, "bin"),
+        _ => panic!("Expected binary"),
+    }
+
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.raw_text, r#"-o:"v ""1""""#);
+            assert_eq!(properties.value_text.as_deref(), Some(r#"v "1""#));
+            assert_eq!(properties.value_raw_text.as_deref(), Some(r#""v ""1""""#));
+        },
+        _ => panic!("Expected option"),
+    }
+
+    match &args[2] {
+        Arg::Param(properties) => {
+            assert_eq!(properties.value_text, r#"1st "Param""#);
+            assert_eq!(properties.raw_text, r#""1st ""Param""""#);
+        },
+        _ => panic!("Expected param"),
+    }
+
+    match &args[3] {
+        Arg::Param(properties) => assert_eq!(properties.raw_text, "p2"),
+        _ => panic!("Expected param"),
+    }
+
+    match &args[4] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.raw_text, "-f");
+            assert_eq!(properties.value_raw_text, None);
+        },
+        _ => panic!("Expected option"),
+    }
+
+    let error = parser.parse_line(r#"bin "1st ""Param"x"#).unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::QuotedParamNotFollowedByWhitespaceChar));
+    assert_eq!(error.context.raw_text, r#""1st ""Param""#);
+}
+
+#[test]
+fn termination() {
+    const COMMAND_LINE: &str = r#"bin "a;b" -f; ñext -x # comment"#;
+
+    let mut parser: Parser = Parser::new();
+    parser.set_parse_terminate_chars(&[';', '#']);
+
+    let (args, termination) = parser.parse_line_with_termination(COMMAND_LINE).unwrap();
+    assert_eq!(args.len(), 3);
+    match &args[2] {
+        Arg::Option(properties) => assert_eq!(properties.code, "f"),
+        _ => panic!("Expected option"),
+    }
+
+    let termination = termination.unwrap();
+    assert_eq!(termination.terminate_char, ';');
+    assert_eq!(termination.span, Span::new(12, 13, 12, 13));
+    assert_eq!(termination.remainder, " ñext -x # comment");
+    assert_eq!(termination.remainder_span, Span::new(13, 31, 13, 32));
+    assert_eq!(&COMMAND_LINE[termination.remainder_span.byte_index..], termination.remainder);
+    assert!(termination.remaining_env_args.is_empty());
+
+    let (args, termination) = parser.parse_line_with_termination(&termination.remainder).unwrap();
+    assert_eq!(args.len(), 2);
+    let termination = termination.unwrap();
+    assert_eq!(termination.terminate_char, '#');
+    assert_eq!(termination.remainder, " comment");
+
+    let (args, termination) = parser.parse_line_with_termination("bin p1").unwrap();
+    assert_eq!(args.len(), 2);
+    assert!(termination.is_none());
+}
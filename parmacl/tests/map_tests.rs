@@ -0,0 +1,98 @@
+use parmacl::{Parser, Arg, Args, DefaultTagType, RegexOrText, OptionHasValue, MapDuplicates, ParseErrorTypeId};
+
+fn pairs<'a>(args: &'a Args<DefaultTagType, DefaultTagType>, arg_idx: usize) -> Vec<(&'a str, &'a str)> {
+    match &args[arg_idx] {
+        Arg::Option(properties) => properties.pairs.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect(),
+        _ => panic!("Expected option"),
+    }
+}
+
+#[test]
+fn map_options() {
+    let mut parser: Parser = Parser::new();
+    parser.set_multi_char_option_code_requires_double_announcer(true);
+    parser
+        .push_new_option_matcher("define")
+            .some_option_codes(&[RegexOrText::with_text("D")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_map_separator('=');
+    parser
+        .push_new_option_matcher("optimise")
+            .some_option_codes(&[RegexOrText::with_text("O")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_map_separator('=')
+            .some_map_key(RegexOrText::with_text("level"));
+    parser
+        .push_new_option_matcher("set")
+            .some_option_codes(&[RegexOrText::with_text("set")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_value_delimiter(',')
+            .some_map_separator('=')
+            .set_map_duplicates(MapDuplicates::Collect);
+    parser
+        .push_new_param_matcher("param");
+
+    let args = parser.parse_line("bin -D A=1 -D NDEBUG --set x=1,y=a=b,x=2 -D A=3 p1").unwrap();
+    assert_eq!(args.len(), 6);
+
+    // A=1 superseded by A=3
+    assert_eq!(pairs(&args, 1), vec![]);
+    assert_eq!(pairs(&args, 2), vec![("NDEBUG", "")]);
+    assert_eq!(pairs(&args, 3), vec![("x", "1"), ("y", "a=b"), ("x", "2")]);
+    assert_eq!(pairs(&args, 4), vec![("A", "3")]);
+
+    match &args[1] {
+        Arg::Option(properties) => assert_eq!(properties.values, vec!["A=1"]),
+        _ => panic!("Expected option"),
+    }
+
+    let args = parser.parse_line("bin -O LEVEL=2").unwrap();
+    assert_eq!(pairs(&args, 1), vec![("LEVEL", "2")]);
+
+    let error = parser.parse_line("bin -O lvl=2").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::InvalidMapKey));
+    assert_eq!(error.context.raw_text, "-O lvl=2");
+
+    let error = parser.parse_line("bin --set =1").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::InvalidMapKey));
+}
+
+#[test]
+fn map_duplicates_error() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("define")
+            .some_option_codes(&[RegexOrText::with_text("D")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_map_separator('=')
+            .set_map_duplicates(MapDuplicates::Error);
+
+    let args = parser.parse_line("bin -D a=1 -D b=2").unwrap();
+    assert_eq!(pairs(&args, 1), vec![("a", "1")]);
+    assert_eq!(pairs(&args, 2), vec![("b", "2")]);
+
+    let error = parser.parse_line("bin -D a=1 -D b=2 -D a=3").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::DuplicateMapKey));
+    assert_eq!(error.option_index, Some(2));
+}
+
+#[test]
+fn map_option_choices() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("mode")
+            .some_option_codes(&[RegexOrText::with_text("m")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_map_separator('=')
+            .set_choices(&["Fast", "Slow"]);
+
+    // choices apply to the pair values, not the whole option value
+    let args = parser.parse_line("bin -m build=fast -m test=SLOW").unwrap();
+    assert_eq!(pairs(&args, 1), vec![("build", "Fast")]);
+    assert_eq!(pairs(&args, 2), vec![("test", "Slow")]);
+
+    let error = parser.parse_line("bin -m build=quick").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::InvalidChoice));
+    assert_eq!(error.param_value_text, "quick");
+    assert_eq!(error.context.candidates, vec!["Fast", "Slow"]);
+}
@@ -0,0 +1,114 @@
+use regex::{Regex, RegexBuilder};
+use parmacl::{Parser, Arg, Args, DefaultTagType, RegexOrText, OptionHasValue};
+
+fn create_parser() -> Parser {
+    let mut parser: Parser = Parser::new();
+    parser.set_multi_char_option_code_requires_double_announcer(true);
+    parser
+        .push_new_option_matcher("first-any-index-0")
+            .some_option_indices(&[0]);
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v"), RegexOrText::with_text("verbose")]);
+    parser
+        .push_new_option_matcher("output")
+            .some_option_codes(&[RegexOrText::with_text("o"), RegexOrText::with_text("OUTPUT")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+        .push_new_option_matcher("color")
+            .some_option_codes(&[RegexOrText::with_text("color")])
+            .some_negation_prefix("no-");
+    parser
+        .push_new_option_matcher("verbose-duplicate")
+            .some_option_codes(&[RegexOrText::with_text("verbose")]);
+    parser
+        .push_new_option_matcher("level")
+            .some_option_codes(&[RegexOrText::with_regex(Regex::new("^l[0-9]$").unwrap())]);
+    parser
+        .push_new_param_matcher("command")
+            .some_param_indices(&[0])
+            .some_value_text(RegexOrText::with_text("build"));
+    parser
+        .push_new_param_matcher("other-command")
+            .some_param_indices(&[0]);
+    parser
+        .push_new_param_matcher("param");
+    parser
+}
+
+fn matcher_names(args: &Args<DefaultTagType, DefaultTagType>) -> Vec<String> {
+    args.iter().map(|arg| match arg {
+        Arg::Binary(_) => String::from("binary"),
+        Arg::Param(properties) => String::from(properties.matcher.name()),
+        Arg::Option(properties) => String::from(properties.matcher.name()),
+    }).collect()
+}
+
+#[test]
+fn compiled_matches_uncompiled() {
+    let uncompiled_parser = create_parser();
+    let mut compiled_parser = create_parser();
+    compiled_parser.compile();
+    assert!(compiled_parser.is_compiled());
+    assert!(!uncompiled_parser.is_compiled());
+
+    let lines = [
+        "bin --x BUILD -V --Output f --l5 --no-color --verbose p1",
+        "bin -v run --verbose -o f --color",
+        "bin --unknown",
+        "bin -o",
+    ];
+    for line in lines {
+        let uncompiled_result = uncompiled_parser.parse_line(line);
+        let compiled_result = compiled_parser.parse_line(line);
+        match (uncompiled_result, compiled_result) {
+            (Ok(uncompiled_args), Ok(compiled_args)) => assert_eq!(matcher_names(&uncompiled_args), matcher_names(&compiled_args)),
+            (Err(uncompiled_error), Err(compiled_error)) => assert_eq!(uncompiled_error.to_string(), compiled_error.to_string()),
+            _ => panic!("Compiled and uncompiled results differ for line: {}", line),
+        }
+    }
+
+    let args = compiled_parser.parse_line("bin --x BUILD -V --Output f --l5 --no-color --verbose p1").unwrap();
+    assert_eq!(matcher_names(&args),
+        vec!["binary", "first-any-index-0", "command", "verbose", "output", "level", "color", "verbose", "param"]
+    );
+}
+
+#[test]
+fn index_discarded_when_matchers_change() {
+    let mut parser = create_parser();
+    parser.compile();
+    parser.push_new_option_matcher("late").some_option_codes(&[RegexOrText::with_text("late")]);
+    assert!(!parser.is_compiled());
+    assert!(parser.parse_line("bin -v --late").is_ok());
+
+    parser.compile();
+    parser.set_option_codes_case_sensitive(true);
+    assert!(!parser.is_compiled());
+}
+
+#[test]
+fn compiled_regex_flags_preserved() {
+    let create_parser = || {
+        let mut parser: Parser = Parser::new();
+        parser.set_option_codes_case_sensitive(true);
+        // the pattern alone does not reproduce the ignore whitespace flag
+        let regex = RegexBuilder::new("^ab c$").ignore_whitespace(true).build().unwrap();
+        parser
+            .push_new_option_matcher("abc")
+                .some_option_codes(&[RegexOrText::with_regex(regex)]);
+        parser
+            .push_new_option_matcher("text")
+                .some_option_codes(&[RegexOrText::with_text("abc"), RegexOrText::try_with_regex_pattern("^x[0-9]$").unwrap()]);
+        parser
+    };
+
+    let uncompiled_parser = create_parser();
+    let mut compiled_parser = create_parser();
+    compiled_parser.compile();
+
+    for parser in [&uncompiled_parser, &compiled_parser] {
+        let args = parser.parse_line("bin -abc -x1").unwrap();
+        assert_eq!(matcher_names(&args), vec!["binary", "abc", "text"]);
+    }
+}
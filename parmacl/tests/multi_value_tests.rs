@@ -0,0 +1,165 @@
+use parmacl::{Parser, Arg, RegexOrText, OptionHasValue, OptionValueArity, ParseErrorTypeId, LineFormatter};
+
+#[test]
+fn delimited_option_values() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .set_multi_char_option_code_requires_double_announcer(true)
+        .set_escape_char(Some('\\'))
+        .set_escapeable_chars(&[',']);
+    parser
+        .push_new_option_matcher("include")
+            .some_option_codes(&[RegexOrText::with_text("include")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_value_delimiter(',');
+    parser
+        .push_new_option_matcher("name")
+            .some_option_codes(&[RegexOrText::with_text("name")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+        .push_new_param_matcher("param");
+
+    let args = parser.parse_line(r#"bin --include a,"b c","d,e",f\,g --name x,y p1,p2"#).unwrap();
+    assert_eq!(args.len(), 4);
+
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.matcher.name(), "include");
+            assert_eq!(properties.values, vec!["a", "b c", "d,e", "f,g"]);
+            assert_eq!(properties.value_text.as_ref().unwrap(), "a,b c,d,e,f,g");
+            assert_eq!(properties.value_raw_text.as_ref().unwrap(), r#"a,"b c","d,e",f\,g"#);
+        },
+        _ => panic!("Expected option"),
+    }
+
+    match &args[2] {
+        Arg::Option(properties) => {
+            // no delimiter in matcher
+            assert_eq!(properties.values, vec!["x,y"]);
+        },
+        _ => panic!("Expected option"),
+    }
+
+    match &args[3] {
+        Arg::Param(properties) => {
+            assert_eq!(properties.value_text, "p1,p2");
+        },
+        _ => panic!("Expected param"),
+    }
+
+    let formatter = LineFormatter::with_parser_style(&parser);
+    assert_eq!(formatter.format_args(&args), r#"bin --include a,"b c","d,e","f,g" --name x,y p1,p2"#);
+
+    let args = parser.parse_line("bin --include single").unwrap();
+    match &args[1] {
+        Arg::Option(properties) => assert_eq!(properties.values, vec!["single"]),
+        _ => panic!("Expected option"),
+    }
+
+    // quoted element must be followed by delimiter or whitespace
+    let error = parser.parse_line(r#"bin --include "a"b"#).unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::QuotedOptionValueNotFollowedByWhitespaceChar));
+}
+
+#[test]
+fn option_value_arity() {
+    let mut parser: Parser = Parser::new();
+    parser.set_multi_char_option_code_requires_double_announcer(true);
+    parser
+        .push_new_option_matcher("point")
+            .some_option_codes(&[RegexOrText::with_text("point")])
+            .set_option_has_value(OptionHasValue::Always)
+            .set_option_value_arity(OptionValueArity::Exactly(3));
+    parser
+        .push_new_option_matcher("files")
+            .some_option_codes(&[RegexOrText::with_text("files")])
+            .set_option_has_value(OptionHasValue::Always)
+            .set_option_value_arity(OptionValueArity::UntilNextOption);
+    parser
+        .push_new_option_matcher("flag")
+            .some_option_codes(&[RegexOrText::with_text("f")]);
+    parser
+        .push_new_param_matcher("param");
+
+    let args = parser.parse_line("bin --point 1 2 3 p1 --files x \"y z\" w -f p2").unwrap();
+    assert_eq!(args.len(), 6);
+
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.matcher.name(), "point");
+            assert_eq!(properties.values, vec!["1", "2", "3"]);
+            assert_eq!(properties.value_text.as_ref().unwrap(), "1");
+        },
+        _ => panic!("Expected option"),
+    }
+
+    match &args[2] {
+        Arg::Param(properties) => {
+            assert_eq!(properties.value_text, "p1");
+            assert_eq!(properties.arg_index, 2);
+            assert_eq!(properties.param_index, 0);
+        },
+        _ => panic!("Expected param"),
+    }
+
+    match &args[3] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.matcher.name(), "files");
+            assert_eq!(properties.values, vec!["x", "y z", "w"]);
+        },
+        _ => panic!("Expected option"),
+    }
+
+    match &args[5] {
+        Arg::Param(properties) => {
+            assert_eq!(properties.value_text, "p2");
+            assert_eq!(properties.arg_index, 5);
+        },
+        _ => panic!("Expected param"),
+    }
+
+    let formatter = LineFormatter::with_parser_style(&parser);
+    assert_eq!(formatter.format_args(&args), "bin --point 1 2 3 p1 --files x \"y z\" w -f p2");
+
+    let error = parser.parse_line("bin --point 1 2 -f").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::OptionMissingValues));
+    assert_eq!(error.option_code.as_deref(), Some("point"));
+    assert_eq!(error.context.raw_text, "--point 1");
+
+    let error = parser.parse_line("bin --point 1 2").unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::OptionMissingValues));
+}
+
+#[test]
+fn option_value_arity_of_zero() {
+    let mut parser: Parser = Parser::new();
+    parser.set_multi_char_option_code_requires_double_announcer(true);
+    parser
+        .push_new_option_matcher("none")
+            .some_option_codes(&[RegexOrText::with_text("none")])
+            .set_option_has_value(OptionHasValue::Always)
+            .set_option_value_arity(OptionValueArity::Exactly(0));
+    parser
+        .push_new_param_matcher("param");
+
+    // Exactly(0) means the option never has a value
+    let args = parser.parse_line("bin --none p1").unwrap();
+    assert_eq!(args.len(), 3);
+
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.matcher.name(), "none");
+            assert_eq!(properties.value_text, None);
+            assert!(properties.values.is_empty());
+        },
+        _ => panic!("Expected option"),
+    }
+
+    match &args[2] {
+        Arg::Param(properties) => {
+            assert_eq!(properties.value_text, "p1");
+            assert_eq!(properties.param_index, 0);
+        },
+        _ => panic!("Expected param"),
+    }
+}
@@ -0,0 +1,79 @@
+use parmacl::{Parser, Arg, RegexOrText, LineFormatter, ValueSource};
+
+#[test]
+fn negatable_flags() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .set_option_announcer_chars(&['-', '+'])
+        .set_multi_char_option_code_requires_double_announcer(true);
+    parser
+        .push_new_option_matcher("color")
+            .some_option_codes(&[RegexOrText::with_text("color")])
+            .some_negation_prefix("no-");
+    parser
+        .push_new_option_matcher("x")
+            .some_option_codes(&[RegexOrText::with_text("x")])
+            .set_negating_announcer_chars(&['-']);
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v")]);
+
+    let args = parser.parse_line("bin --color --No-Color +x -x -v").unwrap();
+    assert_eq!(args.len(), 6);
+
+    let flags: Vec<(&str, bool, char)> = args.iter().skip(1).map(|arg| match arg {
+        Arg::Option(properties) => (properties.matcher.name(), properties.enabled, properties.announcer_char),
+        _ => panic!("Expected option"),
+    }).collect();
+
+    assert_eq!(flags, [
+        ("color", true, '-'),
+        ("color", false, '-'),
+        ("x", true, '+'),
+        ("x", false, '-'),
+        ("verbose", true, '-'),
+    ]);
+
+    let formatter = LineFormatter::with_parser_style(&parser);
+    assert_eq!(formatter.format_args(&args), "bin --color --no-color +x -x -v");
+
+    assert!(parser.parse_line("bin --no-verbose").is_err());
+    assert!(parser.parse_line("bin --no-").is_err());
+}
+
+#[test]
+fn fallback_flags() {
+    let mut parser: Parser = Parser::new();
+    parser.set_multi_char_option_code_requires_double_announcer(true);
+    parser
+        .push_new_option_matcher("color")
+            .some_option_codes(&[RegexOrText::with_text("color")])
+            .some_negation_prefix("no-")
+            .some_default_value("Off");
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v")])
+            .some_default_value("yes");
+
+    let args = parser.parse_line("bin").unwrap();
+    let flags: Vec<(&str, bool, Option<&str>, ValueSource)> = args.iter().skip(1).map(|arg| match arg {
+        Arg::Option(properties) => (properties.matcher.name(), properties.enabled, properties.value_text.as_deref(),
+            properties.value_source.clone()),
+        _ => panic!("Expected option"),
+    }).collect();
+    assert_eq!(flags, [
+        ("color", false, None, ValueSource::Default),
+        ("verbose", true, None, ValueSource::Default),
+    ]);
+
+    // command line takes precedence
+    let args = parser.parse_line("bin --color").unwrap();
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.matcher.name(), "color");
+            assert!(properties.enabled);
+            assert_eq!(properties.value_source, ValueSource::CommandLine);
+        },
+        _ => panic!("Expected option"),
+    }
+}
@@ -0,0 +1,113 @@
+use std::io::{BufReader, Cursor};
+use parmacl::{Parser, Arg, RegexOrText, OptionHasValue, ParseErrorTypeId, ReadCommandError};
+
+#[test]
+fn read_commands() {
+    const INPUT: &str = "load a.txt; run -v\r\n\n  \nsave \"b c\"\nrun -x\nrun -n é; load\n";
+
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v")]);
+    parser
+        .push_new_option_matcher("name")
+            .some_option_codes(&[RegexOrText::with_text("n")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+        .push_new_param_matcher("param");
+
+    let results: Vec<_> = parser.read_commands(BufReader::new(Cursor::new(INPUT))).collect();
+    assert_eq!(results.len(), 6);
+
+    let read_command = results[0].as_ref().unwrap();
+    assert_eq!(read_command.line_number, 1);
+    assert_eq!(read_command.command.separator.as_deref(), Some(";"));
+    match &read_command.command.args[1] {
+        Arg::Param(properties) => assert_eq!(properties.value_text, "a.txt"),
+        _ => panic!("Expected param"),
+    }
+
+    let read_command = results[1].as_ref().unwrap();
+    assert_eq!(read_command.line_number, 1);
+    assert_eq!(read_command.command.args.len(), 2);
+    match &read_command.command.args[1] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.code, "v");
+            assert_eq!(properties.char_index, 16);
+        }
+        _ => panic!("Expected option"),
+    }
+
+    // blank lines skipped
+    let read_command = results[2].as_ref().unwrap();
+    assert_eq!(read_command.line_number, 4);
+    match &read_command.command.args[1] {
+        Arg::Param(properties) => assert_eq!(properties.value_text, "b c"),
+        _ => panic!("Expected param"),
+    }
+
+    match results[3].as_ref().unwrap_err() {
+        ReadCommandError::Parse(error) => {
+            assert!(matches!(error.type_id, ParseErrorTypeId::UnmatchedOption));
+            assert_eq!(error.context.line_number, Some(5));
+            assert_eq!(error.context.column_number, Some(7));
+        }
+        _ => panic!("Expected parse error"),
+    }
+
+    // reading continues after a parse error
+    let read_command = results[4].as_ref().unwrap();
+    assert_eq!(read_command.line_number, 6);
+    match &read_command.command.args[1] {
+        Arg::Option(properties) => assert_eq!(properties.value_text.as_deref(), Some("é")),
+        _ => panic!("Expected option"),
+    }
+
+    let read_command = results[5].as_ref().unwrap();
+    assert_eq!(read_command.line_number, 6);
+    assert_eq!(read_command.command.args.len(), 1);
+}
+
+#[test]
+fn read_commands_invalid_utf8() {
+    let parser: Parser = Parser::new();
+    let input: &[u8] = b"bin a\nbin \xff\nbin b\n";
+
+    let mut reader = parser.read_commands(input);
+    assert!(reader.next().unwrap().is_ok());
+    match reader.next().unwrap() {
+        Err(ReadCommandError::InvalidUtf8 { line_number, error }) => {
+            assert_eq!(line_number, 2);
+            assert_eq!(error.valid_up_to(), 4);
+        }
+        _ => panic!("Expected invalid UTF-8 error"),
+    }
+
+    // invalid line skipped
+    let read_command = reader.next().unwrap().unwrap();
+    assert_eq!(read_command.line_number, 3);
+    assert_eq!(read_command.command.args.len(), 2);
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn read_commands_quote_not_closed_at_end_of_line() {
+    let parser: Parser = Parser::new();
+    let input: &[u8] = b"bin \"a\nb\" c\nbin d\n";
+
+    // a quoted argument cannot continue onto the next line
+    let results: Vec<_> = parser.read_commands(input).collect();
+    assert_eq!(results.len(), 3);
+    match results[0].as_ref().unwrap_err() {
+        ReadCommandError::Parse(error) => {
+            assert!(matches!(error.type_id, ParseErrorTypeId::ParamMissingClosingQuoteCharacter));
+            assert_eq!(error.context.line_number, Some(1));
+        }
+        _ => panic!("Expected parse error"),
+    }
+    // the rest of the quoted argument is parsed as a new line
+    let read_command = results[1].as_ref().unwrap();
+    assert_eq!(read_command.line_number, 2);
+    assert_eq!(read_command.command.args.len(), 2);
+    assert_eq!(results[2].as_ref().unwrap().line_number, 3);
+}
@@ -0,0 +1,110 @@
+use regex::Regex;
+use parmacl::{Parser, Arg, RegexOrText, RegexOptions};
+
+#[test]
+fn regex_filters_option_codes() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("level")
+            .some_option_codes(&[RegexOrText::with_regex(Regex::new("^l[0-9]$").unwrap())]);
+
+    let args = parser.parse_line("bin -l5 -L7").unwrap();
+    assert_eq!(args.len(), 3);
+    for arg in args.iter().skip(1) {
+        match arg {
+            Arg::Option(properties) => assert_eq!(properties.matcher.name(), "level"),
+            _ => panic!("Expected option"),
+        }
+    }
+
+    // regex must not match every code
+    assert!(parser.parse_line("bin -lx").is_err());
+
+    let regex_or_text = RegexOrText::with_regex(Regex::new("^l[0-9]$").unwrap());
+    assert!(regex_or_text.is_regex());
+    assert_eq!(regex_or_text.text(), "^l[0-9]$");
+    assert!(regex_or_text.is_match("L3", false));
+    assert!(!regex_or_text.is_match("L3", true));
+}
+
+#[test]
+fn try_with_regex_pattern() {
+    let regex_or_text = RegexOrText::try_with_regex_pattern("^[a-z]+[0-9]$").unwrap();
+    assert!(regex_or_text.is_regex());
+    assert!(regex_or_text.is_match("abc1", true));
+    assert!(!regex_or_text.is_match("ABC1", true));
+    assert!(regex_or_text.is_match("ABC1", false));
+    assert!(!regex_or_text.is_match("abc", false));
+
+    assert!(RegexOrText::try_with_regex_pattern("(unclosed").is_err());
+}
+
+#[test]
+fn failed_set_leaves_unchanged() {
+    let mut regex_or_text = RegexOrText::with_text("abc");
+    assert!(regex_or_text.try_set_regex_pattern("[").is_err());
+    assert!(!regex_or_text.is_regex());
+    assert_eq!(regex_or_text.text(), "abc");
+    assert!(regex_or_text.is_match("ABC", false));
+
+    regex_or_text.try_set_regex_pattern("^x+$").unwrap();
+    assert!(regex_or_text.try_set_regex_pattern("x{").is_err());
+    assert_eq!(regex_or_text.text(), "^x+$");
+    assert!(regex_or_text.is_match("xxx", true));
+
+    regex_or_text.set_text("^x+$");
+    assert!(!regex_or_text.is_regex());
+    assert!(!regex_or_text.is_match("xxx", true));
+}
+
+#[test]
+fn regex_options() {
+    let mut options = RegexOptions::new();
+    options.set_multi_line(true);
+    let regex_or_text = RegexOrText::try_with_regex_pattern_and_options("^b$", options).unwrap();
+    assert!(regex_or_text.regex_options().multi_line());
+    assert!(regex_or_text.is_match("a\nb", true));
+    assert!(!RegexOrText::try_with_regex_pattern("^b$").unwrap().is_match("a\nb", true));
+
+    let mut options = RegexOptions::new();
+    options.set_unicode(false);
+    let regex_or_text = RegexOrText::try_with_regex_pattern_and_options(r"^\w+$", options).unwrap();
+    assert!(regex_or_text.is_match("abc", true));
+    assert!(!regex_or_text.is_match("日本", true));
+    assert!(RegexOrText::try_with_regex_pattern(r"^\w+$").unwrap().is_match("日本", true));
+
+    let mut options = RegexOptions::new();
+    options.some_size_limit(10_000);
+    assert!(RegexOrText::try_with_regex_pattern_and_options("a{1000}", options.clone()).is_err());
+    assert!(RegexOrText::try_with_regex_pattern_and_options("ab", options).is_ok());
+}
+
+#[test]
+fn try_set_regex_options() {
+    let mut regex_or_text = RegexOrText::try_with_regex_pattern("a{1000}").unwrap();
+    let mut options = RegexOptions::new();
+    options.some_size_limit(10_000);
+    assert!(regex_or_text.try_set_regex_options(options).is_err());
+    assert_eq!(*regex_or_text.regex_options(), RegexOptions::default());
+
+    let mut options = RegexOptions::new();
+    options.set_multi_line(true);
+    let mut regex_or_text = RegexOrText::try_with_regex_pattern("^b$").unwrap();
+    regex_or_text.try_set_regex_options(options).unwrap();
+    assert!(regex_or_text.is_match("a\nb", true));
+    assert!(regex_or_text.is_match("a\nB", false));
+}
+
+#[test]
+fn compiled_parser_with_regex_options() {
+    let mut options = RegexOptions::new();
+    options.set_unicode(false);
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("word")
+            .some_option_codes(&[RegexOrText::try_with_regex_pattern_and_options(r"^\w$", options).unwrap()]);
+    parser.compile();
+
+    assert!(parser.parse_line("bin -a").is_ok());
+    assert!(parser.parse_line("bin -é").is_err());
+}
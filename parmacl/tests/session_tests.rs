@@ -0,0 +1,101 @@
+use parmacl::{Parser, Arg, RegexOrText, OptionHasValue, ParseErrorTypeId};
+
+#[test]
+fn session_reuse() {
+    let mut parser: Parser = Parser::new();
+    parser.set_parse_terminate_chars(&['|']);
+    parser
+        .push_new_option_matcher("name")
+            .some_option_codes(&[RegexOrText::with_text("n")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+        .push_new_param_matcher("param");
+
+    let lines = [
+        "bin -n \"a b\" p1 p2",
+        "bin p3",
+        "bin -x",
+        "bin -n c p4 | ignored",
+    ];
+
+    let mut session = parser.new_session();
+    let mut args = Vec::new();
+
+    session.parse_line(lines[0], &mut args).unwrap();
+    assert_eq!(args.len(), 4);
+    match &args[1] {
+        Arg::Option(properties) => assert_eq!(properties.value_text.as_deref(), Some("a b")),
+        _ => panic!("Expected option"),
+    }
+    let capacity = args.capacity();
+
+    session.parse_line(lines[1], &mut args).unwrap();
+    assert_eq!(args.len(), 2);
+    assert_eq!(args.capacity(), capacity);
+    match &args[1] {
+        Arg::Param(properties) => {
+            assert_eq!(properties.value_text, "p3");
+            assert_eq!(properties.arg_index, 1);
+            assert_eq!(properties.param_index, 0);
+        }
+        _ => panic!("Expected param"),
+    }
+
+    let error = session.parse_line(lines[2], &mut args).unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::UnmatchedOption));
+
+    // session still usable after an error
+    let termination = session.parse_line_with_termination(lines[3], &mut args).unwrap();
+    assert_eq!(termination.unwrap().terminate_char, '|');
+    assert_eq!(args.len(), 3);
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert_eq!(properties.code, "n");
+            assert_eq!(properties.value_text.as_deref(), Some("c"));
+        }
+        _ => panic!("Expected option"),
+    }
+
+    // same results as parsing without a session
+    for line in &lines {
+        let session_result = session.parse_line(line, &mut args).map(|_| format!("{:?}", args)).map_err(|error| error.to_string());
+        let parser_result = parser.parse_line(line).map(|args| format!("{:?}", args)).map_err(|error| error.to_string());
+        assert_eq!(session_result, parser_result);
+    }
+}
+
+#[test]
+fn session_with_reused_line_buffer() {
+    use std::io::BufRead;
+
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("name")
+            .some_option_codes(&[RegexOrText::with_text("n")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+        .push_new_param_matcher("param");
+
+    let mut reader = std::io::Cursor::new("bin -n a p1\nbin p2\n");
+    let mut session = parser.new_session();
+    let mut args = Vec::new();
+    let mut line = String::new();
+    let mut values = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+        session.parse_line_owned(line.trim_end(), &mut args).unwrap();
+        values.push(args.iter().map(|arg| match arg {
+            Arg::Binary(properties) => properties.value_text.to_string(),
+            Arg::Param(properties) => properties.value_text.to_string(),
+            Arg::Option(properties) => properties.value_text.as_deref().unwrap_or("").to_string(),
+        }).collect::<Vec<String>>());
+    }
+    // args can still be used after the line buffer has changed
+    line.clear();
+    assert_eq!(args.len(), 2);
+
+    assert_eq!(values, vec![vec!["bin", "a", "p1"], vec!["bin", "p2"]]);
+}
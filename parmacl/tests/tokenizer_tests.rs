@@ -0,0 +1,128 @@
+use parmacl::{Parser, Arg, TokenKind, Span, ParseErrorTypeId, OptionHasValue};
+
+#[test]
+fn tokens_and_spans() {
+    const COMMAND_LINE: &str = r#"bïn -a:"x y"  "pär ""1""" --flag p2 # rest"#;
+
+    let mut parser: Parser = Parser::new();
+    parser
+        .set_option_value_announcer_chars(&[':'])
+        .set_parse_terminate_chars(&['#']);
+
+    let tokens: Vec<_> = parser.tokenize(COMMAND_LINE).collect::<Result<_, _>>().unwrap();
+
+    let kinds: Vec<TokenKind> = tokens.iter().map(|token| token.kind).collect();
+    assert_eq!(kinds, [
+        TokenKind::Binary,
+        TokenKind::Separator,
+        TokenKind::OptionCode,
+        TokenKind::Separator,
+        TokenKind::OptionValue,
+        TokenKind::Separator,
+        TokenKind::Param,
+        TokenKind::Separator,
+        TokenKind::OptionCode,
+        TokenKind::Separator,
+        TokenKind::Param,
+        TokenKind::Separator,
+        TokenKind::Terminator,
+    ]);
+
+    assert_eq!(tokens[0].text, "bïn");
+    assert_eq!(tokens[0].span, Span::new(0, 3, 0, 4));
+
+    assert_eq!(tokens[2].text, "a");
+    assert_eq!(tokens[2].span, Span::new(4, 6, 5, 7));
+
+    assert_eq!(tokens[3].text, ":");
+
+    assert_eq!(tokens[4].text, "x y");
+    assert_eq!(tokens[4].quote_char, Some('"'));
+    assert_eq!(&COMMAND_LINE[tokens[4].span.byte_index..tokens[4].span.end_byte_index], r#""x y""#);
+
+    assert_eq!(tokens[5].text, "  ");
+
+    assert_eq!(tokens[6].text, r#"pär "1""#);
+    assert!(tokens[6].is_quoted());
+    assert_eq!(tokens[6].span, Span::new(14, 25, 15, 27));
+
+    assert_eq!(tokens[8].text, "-flag");
+    assert_eq!(tokens[10].text, "p2");
+    assert!(!tokens[10].is_quoted());
+
+    assert_eq!(tokens[12].text, "# rest");
+    assert_eq!(tokens[12].span.end_byte_index, COMMAND_LINE.len());
+}
+
+#[test]
+fn tokenize_error() {
+    let parser: Parser = Parser::new();
+    let mut tokenizer = parser.tokenize(r#"bin "unclosed"#);
+
+    assert_eq!(tokenizer.next().unwrap().unwrap().kind, TokenKind::Binary);
+    assert_eq!(tokenizer.next().unwrap().unwrap().kind, TokenKind::Separator);
+    let error = tokenizer.next().unwrap().unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::ParamMissingClosingQuoteCharacter));
+    assert!(tokenizer.next().is_none());
+}
+
+#[test]
+fn tokenizer_agrees_with_parser() {
+    const LINES: [&str; 12] = [
+        r#"  bïn  p1 -a:v1 --flag:"v ""2""" "p 2" par"am3 -b "#,
+        r#"bin p1""p2 "p""3" -c:x"y -d:"" p4"#,
+        r#"bin -m "v 1" -n:"" "p""1" "#,
+        r#"bin \"p1 "p\"2" -e:\"v -f p\\3"#,
+        r#""bin" p1 # -h "unclosed"#,
+        r#"bin#x -i"#,
+        r#"bin -l:v\"#,
+        r#"bin "unclosed"#,
+        r#"bin -j:"unclosed"#,
+        r#"bin "p1"x"#,
+        r#"bin p1\"#,
+        r#"bin -k"x"#,
+    ];
+
+    let mut parser: Parser = Parser::new();
+    parser
+        .set_option_value_announcer_chars(&[' ', ':'])
+        .set_parse_terminate_chars(&['#'])
+        .some_escape_char('\\');
+    parser
+        .push_new_option_matcher("option")
+            .set_option_has_value(OptionHasValue::IfPossible);
+    parser
+        .push_new_param_matcher("param");
+
+    for line in LINES {
+        let tokens: Result<Vec<_>, _> = parser.tokenize(line).collect();
+        match parser.parse_line(line) {
+            Ok(args) => {
+                let tokens = tokens.unwrap();
+                let token_texts: Vec<&str> = tokens.iter()
+                    .filter(|token| !matches!(token.kind, TokenKind::Separator | TokenKind::Terminator))
+                    .map(|token| token.text.as_str())
+                    .collect();
+                let mut arg_texts: Vec<&str> = Vec::new();
+                for arg in args.iter() {
+                    match arg {
+                        Arg::Binary(properties) => arg_texts.push(&properties.value_text),
+                        Arg::Param(properties) => arg_texts.push(&properties.value_text),
+                        Arg::Option(properties) => {
+                            arg_texts.push(&properties.code);
+                            if let Some(value_text) = &properties.value_text {
+                                arg_texts.push(value_text);
+                            }
+                        }
+                    }
+                }
+                assert_eq!(token_texts, arg_texts, "line: {line}");
+            }
+            Err(parse_error) => {
+                let token_error = tokens.unwrap_err();
+                assert_eq!(token_error.type_id.to_string(), parse_error.type_id.to_string(), "line: {line}");
+                assert_eq!(token_error.line_char_index, parse_error.line_char_index, "line: {line}");
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use parmacl::{Parser, RegexOrText, OptionHasValue, ValidationIssueTypeId, ValidationSeverity};
+
+#[test]
+fn valid_parser() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v")]);
+    parser
+        .push_new_option_matcher("level")
+            .some_option_codes(&[RegexOrText::with_text("l")])
+            .set_option_has_value(OptionHasValue::IfPossible);
+    parser
+        .push_new_option_matcher("fast-level")
+            .some_option_codes(&[RegexOrText::with_text("f")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_value_text(RegexOrText::with_text("fast"));
+    parser
+        .push_new_option_matcher("any-level")
+            .some_option_codes(&[RegexOrText::with_text("f")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+        .push_new_param_matcher("param");
+
+    assert!(parser.validate().is_empty());
+}
+
+#[test]
+fn invalid_parser() {
+    let mut parser: Parser = Parser::new();
+    parser.set_option_value_announcer_chars(&['=']);
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v"), RegexOrText::with_text("verbose")]);
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("VERBOSE")]);
+    parser
+        .push_new_option_matcher("level")
+            .some_option_codes(&[RegexOrText::with_text("l")])
+            .set_option_has_value(OptionHasValue::IfPossible);
+    parser
+        .push_new_option_matcher("level-value")
+            .some_option_codes(&[RegexOrText::with_text("l")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+        .push_new_param_matcher("param")
+            .some_option_codes(&[RegexOrText::with_text("p")]);
+
+    let issues = parser.validate();
+    let issue_summaries: Vec<(ValidationIssueTypeId, usize, Option<usize>)> = issues.iter()
+        .map(|issue| (issue.type_id, issue.matcher_index, issue.other_matcher_index)).collect();
+    assert_eq!(issue_summaries, [
+        (ValidationIssueTypeId::DuplicateMatcherName, 1, Some(0)),
+        (ValidationIssueTypeId::UnreachableOptionMatcher, 1, Some(0)),
+        (ValidationIssueTypeId::IfPossibleWithoutWhitespaceValueAnnouncer, 2, None),
+        (ValidationIssueTypeId::UnreachableOptionMatcher, 3, Some(2)),
+        (ValidationIssueTypeId::ParamMatcherHasOptionCodes, 4, None),
+    ]);
+
+    assert_eq!(issues[0].severity, ValidationSeverity::Error);
+    assert_eq!(issues[1].severity, ValidationSeverity::Warning);
+    assert_eq!(issues[1].to_string(), "warning: Option matcher is unreachable [m:1 \"verbose\" other:0]");
+}
+
+#[test]
+fn case_sensitive_codes_are_reachable() {
+    let mut parser: Parser = Parser::new();
+    parser.set_option_codes_case_sensitive(true);
+    parser
+        .push_new_option_matcher("lower")
+            .some_option_codes(&[RegexOrText::with_text("v")]);
+    parser
+        .push_new_option_matcher("upper")
+            .some_option_codes(&[RegexOrText::with_text("V")]);
+
+    assert!(parser.validate().is_empty());
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use parmacl::{Parser, Arg, RegexOrText, OptionHasValue, VariableSyntax};
+
+#[test]
+fn expand_variables() {
+    const COMMAND_LINE: &str = r#"$BIN --out $HOME/x %TEMP%\y ${MISSING:-dflt} ${EMPTY:-e} ^$HOME 'lit $HOME' "$UNKNOWN ${HOME}""#;
+
+    let mut variables = HashMap::new();
+    variables.insert(String::from("HOME"), String::from("/home/me"));
+    variables.insert(String::from("TEMP"), String::from("C:\\Temp"));
+    variables.insert(String::from("BIN"), String::from("tool"));
+    variables.insert(String::from("EMPTY"), String::from(""));
+
+    let mut parser: Parser = Parser::new();
+    parser
+        .set_quote_chars(&['"', '\''])
+        .set_multi_char_option_code_requires_double_announcer(true)
+        .some_escape_char('^')
+        .set_escapeable_chars(&['$'])
+        .set_variable_syntaxes(&[VariableSyntax::Dollar, VariableSyntax::DollarBracesDefault, VariableSyntax::Percent])
+        .set_variable_literal_quote_chars(&['\''])
+        .set_variable_resolver(variables);
+
+    parser
+        .push_new_option_matcher("output")
+            .some_option_codes(&[RegexOrText::with_text("out")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+        .push_new_param_matcher("param");
+
+    let args = parser.parse_line(COMMAND_LINE).unwrap();
+    assert_eq!(args.len(), 8);
+
+    let value_texts: Vec<&str> = args.iter().map(|arg| match arg {
+        Arg::Binary(properties) => properties.value_text.as_ref(),
+        Arg::Param(properties) => properties.value_text.as_ref(),
+        Arg::Option(properties) => properties.value_text.as_deref().unwrap(),
+    }).collect();
+
+    assert_eq!(value_texts, [
+        "$BIN",
+        "/home/me/x",
+        "C:\\Temp\\y",
+        "dflt",
+        "e",
+        "$HOME",
+        "lit $HOME",
+        "$UNKNOWN /home/me",
+    ]);
+
+    match &args[1] {
+        Arg::Option(properties) => assert_eq!(properties.value_raw_text.as_deref(), Some("$HOME/x")),
+        _ => panic!("Expected option"),
+    }
+}
+
+#[test]
+fn expansion_is_opt_in_and_resolver_is_pluggable() {
+    let mut parser: Parser = Parser::new();
+    let args = parser.parse_line("bin $X").unwrap();
+    match &args[1] {
+        Arg::Param(properties) => assert_eq!(properties.value_text, "$X"),
+        _ => panic!("Expected param"),
+    }
+
+    parser
+        .set_variable_syntaxes(&[VariableSyntax::DollarBraces])
+        .set_variable_resolver(|name: &str| if name == "X" { Some(String::from("ex")) } else { None });
+    let args = parser.parse_line("bin ${X} $X ${X:-y}").unwrap();
+    let value_texts: Vec<&str> = args.iter().skip(1).map(|arg| match arg {
+        Arg::Param(properties) => properties.value_text.as_ref(),
+        _ => panic!("Expected param"),
+    }).collect();
+    assert_eq!(value_texts, ["ex", "$X", "${X:-y}"]);
+}
+
+#[test]
+fn parser_with_resolver_can_be_shared_between_threads() {
+    let mut variables = HashMap::new();
+    variables.insert(String::from("X"), String::from("ex"));
+    let mut parser: Parser = Parser::new();
+    parser
+        .set_variable_syntaxes(&[VariableSyntax::DollarBraces])
+        .set_variable_resolver(variables);
+
+    let parser = &parser;
+    std::thread::scope(|scope| {
+        let handle = scope.spawn(move || {
+            let args = parser.parse_line("bin ${X}").unwrap();
+            match &args[1] {
+                Arg::Param(properties) => String::from(properties.value_text.as_ref()),
+                _ => panic!("Expected param"),
+            }
+        });
+        assert_eq!(handle.join().unwrap(), "ex");
+    });
+}
@@ -0,0 +1,108 @@
+use std::ops::ControlFlow;
+use parmacl::{Parser, ArgVisitor, BinaryProperties, OptionProperties, ParamProperties, ParseError, ParseErrorTypeId, RegexOrText,
+    OptionHasValue, OptionValueArity, DefaultTagType};
+
+#[derive(Default)]
+struct RecordingVisitor {
+    events: Vec<String>,
+    stop_at_param: bool,
+}
+
+impl<'a> ArgVisitor<'a> for RecordingVisitor {
+    fn on_binary(&mut self, properties: BinaryProperties<'a, DefaultTagType, DefaultTagType>) -> ControlFlow<()> {
+        self.events.push(format!("b:{}", properties.value_text));
+        ControlFlow::Continue(())
+    }
+
+    fn on_option(&mut self, properties: OptionProperties<'a, DefaultTagType, DefaultTagType>) -> ControlFlow<()> {
+        if properties.values.is_empty() {
+            self.events.push(format!("o:{}", properties.code));
+        } else {
+            self.events.push(format!("o:{}={}", properties.code, properties.values.join(",")));
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn on_param(&mut self, properties: ParamProperties<'a, DefaultTagType, DefaultTagType>) -> ControlFlow<()> {
+        self.events.push(format!("p:{}", properties.value_text));
+        if self.stop_at_param {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    fn on_error(&mut self, error: &ParseError) {
+        self.events.push(format!("e:{}", error.type_id.get_default_text()));
+    }
+
+    fn is_option_value(&mut self, _option_code: &str, value_text: &str) -> bool {
+        !value_text.starts_with(char::is_uppercase)
+    }
+}
+
+fn create_parser() -> Parser {
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("x")
+            .some_option_codes(&[RegexOrText::with_text("x")])
+            .set_option_has_value(OptionHasValue::IfPossible);
+    parser
+        .push_new_option_matcher("point")
+            .some_option_codes(&[RegexOrText::with_text("p")])
+            .set_option_has_value(OptionHasValue::Always)
+            .set_option_value_arity(OptionValueArity::Exactly(2));
+    parser
+        .push_new_option_matcher("level")
+            .some_option_codes(&[RegexOrText::with_text("l")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_default_value("3");
+    parser
+        .push_new_param_matcher("param");
+    parser
+}
+
+#[test]
+fn visit_args() {
+    let parser = create_parser();
+
+    let mut visitor = RecordingVisitor::default();
+    let termination = parser.parse_line_with("bin -x val -x Word -p 1 2 q1 -x", &mut visitor).unwrap();
+    assert!(termination.is_none());
+    assert_eq!(visitor.events, ["b:bin", "o:x=val", "o:x", "p:Word", "o:p=1,2", "p:q1", "o:x", "o:l=3"]);
+
+    // without visitor, "Word" is the value of "-x"
+    let args = parser.parse_line("bin -x val -x Word -p 1 2 q1 -x").unwrap();
+    assert_eq!(args.len(), 7);
+}
+
+#[test]
+fn visit_abort_and_error() {
+    let parser = create_parser();
+
+    let mut visitor = RecordingVisitor { stop_at_param: true, ..Default::default() };
+    let termination = parser.parse_line_with("bin a -x b", &mut visitor).unwrap();
+    assert!(termination.is_none());
+    assert_eq!(visitor.events, ["b:bin", "p:a"]);
+
+    let mut visitor = RecordingVisitor::default();
+    let error = parser.parse_line_with("bin a -z b", &mut visitor).unwrap_err();
+    assert!(matches!(error.type_id, ParseErrorTypeId::UnmatchedOption));
+    assert_eq!(visitor.events, ["b:bin", "p:a", "e:Option not matched"]);
+}
+
+#[test]
+fn visit_counted_options() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("verbose")
+            .some_option_codes(&[RegexOrText::with_text("v")])
+            .set_counted(true);
+
+    // unlike parse_line, repeated counted options are passed separately
+    let mut visitor = RecordingVisitor::default();
+    parser.parse_line_with("bin -v -vv", &mut visitor).unwrap();
+    assert_eq!(visitor.events, ["b:bin", "o:v", "o:vv"]);
+    let args = parser.parse_line("bin -v -vv").unwrap();
+    assert_eq!(args.len(), 2);
+}
@@ -0,0 +1,106 @@
+use std::borrow::Cow;
+use parmacl::{Parser, Arg, RegexOrText, OptionHasValue, OptionValueArity};
+
+#[test]
+fn borrowed_from_line() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .set_escape_char(Some('\\'))
+        .set_escapeable_chars(&['x']);
+    parser
+        .push_new_option_matcher("name")
+            .some_option_codes(&[RegexOrText::with_text("n")])
+            .set_option_has_value(OptionHasValue::Always);
+    parser
+        .push_new_param_matcher("param");
+
+    let args = parser.parse_line(r#"bin -n "a b" p1 "p""2" p\x3 -n "c""d""#).unwrap();
+    assert_eq!(args.len(), 6);
+
+    match &args[0] {
+        Arg::Binary(properties) => assert!(matches!(properties.value_text, Cow::Borrowed("bin"))),
+        _ => panic!("Expected binary"),
+    }
+
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert!(matches!(properties.code, Cow::Borrowed("n")));
+            assert!(matches!(properties.value_text, Some(Cow::Borrowed("a b"))));
+            assert!(matches!(properties.value_raw_text, Some(Cow::Borrowed(r#""a b""#))));
+            assert!(matches!(properties.raw_text, Cow::Borrowed(r#"-n "a b""#)));
+        },
+        _ => panic!("Expected option"),
+    }
+
+    match &args[2] {
+        Arg::Param(properties) => assert!(matches!(properties.value_text, Cow::Borrowed("p1"))),
+        _ => panic!("Expected param"),
+    }
+
+    // embedded quote and escape require a copy
+    match &args[3] {
+        Arg::Param(properties) => {
+            assert!(matches!(properties.value_text, Cow::Owned(_)));
+            assert_eq!(properties.value_text, "p\"2");
+            assert!(matches!(properties.raw_text, Cow::Borrowed(r#""p""2""#)));
+        },
+        _ => panic!("Expected param"),
+    }
+
+    match &args[4] {
+        Arg::Param(properties) => {
+            assert!(matches!(properties.value_text, Cow::Owned(_)));
+            assert_eq!(properties.value_text, "px3");
+        },
+        _ => panic!("Expected param"),
+    }
+
+    match &args[5] {
+        Arg::Option(properties) => {
+            assert!(matches!(properties.code, Cow::Borrowed("n")));
+            assert!(matches!(properties.value_text, Some(Cow::Owned(_))));
+            assert_eq!(properties.value_text.as_deref(), Some("c\"d"));
+        },
+        _ => panic!("Expected option"),
+    }
+}
+
+#[test]
+fn option_values_borrowed_from_line() {
+    let mut parser: Parser = Parser::new();
+    parser
+        .push_new_option_matcher("list")
+            .some_option_codes(&[RegexOrText::with_text("l")])
+            .set_option_has_value(OptionHasValue::Always)
+            .some_value_delimiter(',');
+    parser
+        .push_new_option_matcher("point")
+            .some_option_codes(&[RegexOrText::with_text("p")])
+            .set_option_has_value(OptionHasValue::Always)
+            .set_option_value_arity(OptionValueArity::Exactly(2));
+
+    let args = parser.parse_line(r#"bin -l a,b -l x,"y z" -p 1 "2""#).unwrap();
+
+    match &args[1] {
+        Arg::Option(properties) => {
+            assert!(matches!(properties.values[..], [Cow::Borrowed("a"), Cow::Borrowed("b")]));
+        },
+        _ => panic!("Expected option"),
+    }
+
+    match &args[2] {
+        // quotes removed so values cannot be borrowed
+        Arg::Option(properties) => {
+            assert!(matches!(properties.values[..], [Cow::Owned(_), Cow::Owned(_)]));
+            assert_eq!(properties.values, vec!["x", "y z"]);
+        },
+        _ => panic!("Expected option"),
+    }
+
+    match &args[3] {
+        Arg::Option(properties) => {
+            assert!(matches!(properties.values[..], [Cow::Borrowed("1"), Cow::Borrowed("2")]));
+        },
+        _ => panic!("Expected option"),
+    }
+}